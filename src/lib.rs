@@ -3,24 +3,132 @@
 //! WAVE PCM is a library for fast encoding and decoding of WAV PCM format files.
 //! As the name suggests, the library only supports the PCM version of WAVE format specification.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(clippy::all, clippy::pedantic, missing_docs)]
 
-use std::convert::TryInto;
-use std::fs::File;
-use std::io::{prelude::Read, BufReader, BufWriter, Write};
+extern crate alloc;
 
-// Read 2 bytes from a reader.
+use alloc::{borrow::Cow, format, string::String, vec, vec::Vec};
+use core::convert::TryInto;
+#[cfg(feature = "std")]
+use std::fs::{File, OpenOptions};
+#[cfg(feature = "std")]
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+// Source of bytes for the decoder.
+//
+// Unlike `std::io::Read`, a source never fails: an I/O error ends the source just like the end
+// of the data does, so the decoder can run without `std`.
+trait ByteSource {
+    // Fill as much of a buffer as possible and return the number of bytes read.
+    //
+    // # Arguments
+    //
+    // * `buf` - Output buffer.
+    fn fill(&mut self, buf: &mut [u8]) -> usize;
+}
+
+impl ByteSource for &[u8] {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let nbytes = buf.len().min(self.len());
+        let (head, tail) = self.split_at(nbytes);
+        buf[..nbytes].copy_from_slice(head);
+        *self = tail;
+        nbytes
+    }
+}
+
+// Byte source reading from a `std::io::Read` implementation.
+#[cfg(feature = "std")]
+struct IoSource<T>(T);
+
+#[cfg(feature = "std")]
+impl<T> ByteSource for IoSource<T>
+where
+    T: Read,
+{
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let mut nbytes = 0;
+        while nbytes < buf.len() {
+            match self.0.read(&mut buf[nbytes..]) {
+                Ok(0) => break,
+                Ok(n) => nbytes += n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        nbytes
+    }
+}
+
+// Byte source reporting the number of bytes read every `PROGRESS_BLOCK` bytes.
+#[cfg(feature = "std")]
+struct ProgressSource<S, F> {
+    source: S,
+    bytes_read: u64,
+    total_bytes: u64,
+    next_report: u64,
+    on_progress: F,
+}
+
+// Number of bytes read between two progress reports.
+#[cfg(feature = "std")]
+const PROGRESS_BLOCK: usize = 64 * 1024;
+
+#[cfg(feature = "std")]
+impl<S, F> ByteSource for ProgressSource<S, F>
+where
+    S: ByteSource,
+    F: FnMut(u64, u64),
+{
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let mut nbytes = 0;
+        for block in buf.chunks_mut(PROGRESS_BLOCK) {
+            let n = self.source.fill(block);
+            nbytes += n;
+            self.bytes_read += n as u64;
+            if self.bytes_read >= self.next_report {
+                (self.on_progress)(self.bytes_read, self.total_bytes);
+                self.next_report = self.bytes_read + PROGRESS_BLOCK as u64;
+            }
+            if n < block.len() {
+                break;
+            }
+        }
+        nbytes
+    }
+}
+
+// Read exactly `N` bytes of a header field from a reader, advancing the running offset.
 //
 // # Arguments
 //
 // * `reader` - A reader.
-fn read2<T>(reader: &mut T) -> [u8; 2]
+// * `field` - Name of the field, used in the error message.
+// * `offset` - Byte offset of the field within the file.
+//
+// # Errors
+//
+// If the reader ends before the field is complete.
+fn read_exact<T, const N: usize>(
+    reader: &mut T,
+    field: &str,
+    offset: &mut usize,
+) -> Result<[u8; N], anyhow::Error>
 where
-    T: Read,
+    T: ByteSource,
 {
-    let mut buf = [0_u8; 2];
-    let _nbytes = reader.read(&mut buf);
-    buf
+    let mut buf = [0_u8; N];
+    let nbytes = reader.fill(&mut buf);
+    if nbytes < N {
+        return Err(anyhow::anyhow!(
+            "Failed reading {field} at offset {offset}, got {nbytes} of {N} bytes instead."
+        ));
+    }
+    *offset += N;
+    Ok(buf)
 }
 
 // Read 4 bytes from a reader.
@@ -30,10 +138,10 @@ where
 // * `reader` - A reader.
 fn read4<T>(reader: &mut T) -> [u8; 4]
 where
-    T: Read,
+    T: ByteSource,
 {
     let mut buf = [0_u8; 4];
-    let _nbytes = reader.read(&mut buf);
+    let _nbytes = reader.fill(&mut buf);
     buf
 }
 
@@ -45,122 +153,8257 @@ where
 //
 // # Errors
 //
-// If the value cannot fit when performing type conversion.
-fn readn<T>(reader: T, nbytes: u32) -> Result<Vec<u8>, anyhow::Error>
+// If the size exceeds addressable memory on this platform.
+fn readn<T>(reader: &mut T, nbytes: u32) -> Result<Vec<u8>, anyhow::Error>
 where
-    T: Read,
+    T: ByteSource,
 {
-    let mut buf = Vec::with_capacity(nbytes.try_into()?);
-    let mut chunk = reader.take(u64::from(nbytes));
-    let _val = chunk.read_to_end(&mut buf);
+    let Ok(len) = usize::try_from(nbytes) else {
+        return Err(anyhow::anyhow!(
+            "Chunk size of {nbytes} bytes exceeds addressable memory on this platform."
+        ));
+    };
+
+    // Grow the buffer as bytes arrive, so a corrupt size does not allocate up front.
+    let mut buf = Vec::new();
+    while buf.len() < len {
+        let start = buf.len();
+        buf.resize(start + (len - start).min(READ_BLOCK), 0);
+        let nbytes = reader.fill(&mut buf[start..]);
+        buf.truncate(start + nbytes);
+        if nbytes == 0 {
+            break;
+        }
+    }
     Ok(buf)
 }
 
-/// WAVE PCM file format.
-pub struct Format {
-    /// RIFF tag ("RIFF").
-    pub riff_tag: [u8; 4],
-    /// Total size of a file in bytes.
-    pub total_size: [u8; 4],
-    /// WAVE tag ("WAVE").
-    pub wave_tag: [u8; 4],
-    /// Format tag ("fmt ").
-    pub fmt_chunk_tag: [u8; 4],
-    /// Format chunk size (16 for PCM).
-    pub fmt_chunk_size: [u8; 4],
-    /// Format type (1 for PCM - uncompressed).
-    pub fmt_code: [u8; 2],
-    /// Number of channels in the audio data.
-    pub num_channels: [u8; 2],
-    /// Sampling rate in the audio data (blocks per second).
-    pub sampling_rate: [u8; 4],
-    /// Byte rate (sampling_rate * num_channels * bits_per_sample / 8).
-    pub byte_rate: [u8; 4],
-    /// Block alignment value (num_channels * bits_per_sample / 8).
-    pub block_alignment: [u8; 2],
-    /// Bits per sample in the audio data (8 - 8 bits, 16 - 16 bits, etc).
-    pub bits_per_sample: [u8; 2],
-    /// Data tag ("data").
-    pub data_tag: [u8; 4],
-    /// Size of the audio data (num_samples * num_channels * bits_per_sample / 8).
-    pub data_size: [u8; 4],
-    /// Raw audio data.
-    pub data: Vec<u8>,
+// Read the body of a chunk, skipping the padding byte after a body of odd size.
+//
+// # Arguments
+//
+// * `reader` - A reader.
+// * `nbytes` - Size of the chunk body.
+//
+// # Errors
+//
+// If the size exceeds addressable memory on this platform.
+fn read_chunk_body<T>(reader: &mut T, nbytes: u32) -> Result<Vec<u8>, anyhow::Error>
+where
+    T: ByteSource,
+{
+    let body = readn(reader, nbytes)?;
+
+    // Chunks are aligned to 2 bytes.
+    if nbytes % 2 == 1 {
+        skip(reader, 1);
+    }
+    Ok(body)
 }
 
-impl Format {
-    /// `encode` encodes WAVE PCM file.
+// Number of bytes written between two flushes by `Format::write_with_progress`.
+#[cfg(feature = "std")]
+const WRITE_BLOCK: usize = 1024 * 1024;
+
+// Number of bytes read at a time by `readn`.
+const READ_BLOCK: usize = 1024 * 1024;
+
+// Skip an arbitrary number of bytes from a reader.
+//
+// # Arguments
+//
+// * `reader` - A reader.
+// * `nbytes` - Number of bytes to skip.
+fn skip<T>(reader: &mut T, nbytes: u32)
+where
+    T: ByteSource,
+{
+    let mut buf = [0_u8; 4096];
+    let mut remaining = nbytes as usize;
+    while remaining > 0 {
+        let chunk = remaining.min(buf.len());
+        if reader.fill(&mut buf[..chunk]) < chunk {
+            break;
+        }
+        remaining -= chunk;
+    }
+}
+
+// Read a little-endian 32-bit value from a byte slice.
+//
+// # Arguments
+//
+// * `bytes` - Byte slice.
+// * `offset` - Offset of the value within the slice.
+fn le_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+// Convert little-endian 16-bit PCM bytes to samples.
+//
+// # Arguments
+//
+// * `data` - Raw audio data.
+#[cfg(feature = "std")]
+fn bytes_to_i16(data: &[u8]) -> Vec<i16> {
+    data.chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+// Convert 16-bit samples to little-endian PCM bytes.
+//
+// # Arguments
+//
+// * `samples` - Audio samples.
+#[cfg(feature = "std")]
+fn i16_to_bytes(samples: &[i16]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|sample| sample.to_le_bytes())
+        .collect()
+}
+
+// Round and saturate a floating point sample to 16 bits.
+//
+// # Arguments
+//
+// * `sample` - Audio sample.
+#[cfg(feature = "std")]
+#[allow(clippy::cast_possible_truncation)]
+fn saturate_i16(sample: f64) -> i16 {
+    sample
+        .round()
+        .clamp(f64::from(i16::MIN), f64::from(i16::MAX)) as i16
+}
+
+// Round and saturate a floating point sample to 32 bits.
+//
+// # Arguments
+//
+// * `sample` - Audio sample.
+#[cfg(feature = "std")]
+#[allow(clippy::cast_possible_truncation)]
+fn saturate_i32(sample: f64) -> i32 {
+    sample
+        .round()
+        .clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32
+}
+
+/// Coefficients of a biquad filter section normalized by `a0`.
+///
+/// Each section computes `y[n] = b0 x[n] + b1 x[n-1] + b2 x[n-2] - a1 y[n-1] - a2 y[n-2]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BiquadCoeffs {
+    /// Feedforward gain of the current input.
+    pub b0: f64,
+    /// Feedforward gain of the previous input.
+    pub b1: f64,
+    /// Feedforward gain of the input before the previous one.
+    pub b2: f64,
+    /// Feedback gain of the previous output.
+    pub a1: f64,
+    /// Feedback gain of the output before the previous one.
+    pub a2: f64,
+}
+
+#[cfg(feature = "std")]
+impl BiquadCoeffs {
+    // Compute lowpass or highpass coefficients from the Robert Bristow-Johnson cookbook.
+    //
+    // # Arguments
+    //
+    // * `cutoff_hz` - Cutoff frequency, clamped below the Nyquist frequency.
+    // * `sampling_rate` - Sampling rate in the audio data.
+    // * `highpass` - Whether to compute highpass instead of lowpass coefficients.
+    fn new(cutoff_hz: f64, sampling_rate: u32, highpass: bool) -> Self {
+        let sampling_rate = f64::from(sampling_rate);
+        let cutoff_hz = cutoff_hz.min(sampling_rate * 0.499);
+        let w0 = 2.0 * std::f64::consts::PI * cutoff_hz / sampling_rate;
+        let alpha = w0.sin() / (2.0 * std::f64::consts::FRAC_1_SQRT_2);
+        let cos_w0 = w0.cos();
+
+        let (b0, b1) = if highpass {
+            (f64::midpoint(1.0, cos_w0), -(1.0 + cos_w0))
+        } else {
+            ((1.0 - cos_w0) / 2.0, 1.0 - cos_w0)
+        };
+        let a0 = 1.0 + alpha;
+
+        BiquadCoeffs {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b0 / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+
+    // Compute the lowpass filter removing content above the Nyquist frequency of a lower
+    // sampling rate.
+    //
+    // # Arguments
+    //
+    // * `source_rate` - Sampling rate of the audio data.
+    // * `sampling_rate` - Sampling rate the audio data will be resampled to.
+    fn anti_alias(source_rate: u32, sampling_rate: u32) -> Self {
+        BiquadCoeffs::new(0.45 * f64::from(sampling_rate), source_rate, false)
+    }
+
+    // Compute the two stages of the ITU-R BS.1770 K-weighting filter.
+    //
+    // # Arguments
+    //
+    // * `sampling_rate` - Sampling rate in the audio data.
+    fn k_weighting(sampling_rate: u32) -> [Self; 2] {
+        let sampling_rate = f64::from(sampling_rate);
+
+        // High shelf modeling the acoustic effect of the head.
+        let k = (std::f64::consts::PI * 1_681.974_450_955_533 / sampling_rate).tan();
+        let q = 0.707_175_236_955_419_6;
+        let vh = 10_f64.powf(3.999_843_853_973_347 / 20.0);
+        let vb = vh.powf(0.499_666_774_154_541_6);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = BiquadCoeffs {
+            b0: (vh + vb * k / q + k * k) / a0,
+            b1: 2.0 * (k * k - vh) / a0,
+            b2: (vh - vb * k / q + k * k) / a0,
+            a1: 2.0 * (k * k - 1.0) / a0,
+            a2: (1.0 - k / q + k * k) / a0,
+        };
+
+        // Revised low-frequency B-weighting highpass.
+        let k = (std::f64::consts::PI * 38.135_470_876_024_44 / sampling_rate).tan();
+        let q = 0.500_327_037_323_877_3;
+        let a0 = 1.0 + k / q + k * k;
+        let highpass = BiquadCoeffs {
+            b0: 1.0,
+            b1: -2.0,
+            b2: 1.0,
+            a1: 2.0 * (k * k - 1.0) / a0,
+            a2: (1.0 - k / q + k * k) / a0,
+        };
+
+        [shelf, highpass]
+    }
+
+    // Filter interleaved samples, running one filter state per channel.
+    //
+    // # Arguments
+    //
+    // * `samples` - Interleaved audio samples.
+    // * `num_channels` - Number of channels in the audio data.
+    fn apply(&self, samples: &[i16], num_channels: usize) -> Vec<i16> {
+        let samples: Vec<f64> = samples.iter().map(|&sample| f64::from(sample)).collect();
+        self.apply_f64(&samples, num_channels)
+            .into_iter()
+            .map(saturate_i16)
+            .collect()
+    }
+
+    // Filter interleaved floating point samples, running one filter state per channel.
+    //
+    // # Arguments
+    //
+    // * `samples` - Interleaved audio samples.
+    // * `num_channels` - Number of channels in the audio data.
+    fn apply_f64(&self, samples: &[f64], num_channels: usize) -> Vec<f64> {
+        let mut state = vec![[0.0_f64; 4]; num_channels];
+        samples
+            .iter()
+            .enumerate()
+            .map(|(idx, &x0)| {
+                let [x1, x2, y1, y2] = &mut state[idx % num_channels];
+                let y0 =
+                    self.b0 * x0 + self.b1 * *x1 + self.b2 * *x2 - self.a1 * *y1 - self.a2 * *y2;
+                *x2 = *x1;
+                *x1 = x0;
+                *y2 = *y1;
+                *y1 = y0;
+                y0
+            })
+            .collect()
+    }
+}
+
+/// `interleave_i16` interleaves 16-bit channels into little-endian audio data.
+///
+/// The result is ready to be passed to [`Format::encode`].
+///
+/// # Arguments
+///
+/// * `channels` - Samples of every channel.
+///
+/// # Errors
+///
+/// If there are no channels or the channels differ in length.
+///
+/// # Example
+///
+/// ```
+/// fn main() -> Result<(), anyhow::Error> {
+///     let left = [1_i16, 3];
+///     let right = [2_i16, -4];
+///     let data = wavepcm::interleave_i16(&[&left, &right])?;
+///     assert_eq!(data, [1, 0, 2, 0, 3, 0, 252, 255]);
+///     Ok(())
+/// }
+/// ```
+pub fn interleave_i16(channels: &[&[i16]]) -> Result<Vec<u8>, anyhow::Error> {
+    let Some(first) = channels.first() else {
+        return Err(anyhow::anyhow!(
+            "Interleaving requires at least one channel, got 0 instead."
+        ));
+    };
+
+    if let Some((idx, channel)) = channels
+        .iter()
+        .enumerate()
+        .find(|(_, channel)| channel.len() != first.len())
+    {
+        return Err(anyhow::anyhow!(
+            "Interleaving requires channels of equal length, channel 0 has {} samples and channel {idx} has {} instead.",
+            first.len(),
+            channel.len()
+        ));
+    }
+
+    let mut data = Vec::with_capacity(first.len() * channels.len() * 2);
+    for frame in 0..first.len() {
+        for channel in channels {
+            data.extend_from_slice(&channel[frame].to_le_bytes());
+        }
+    }
+
+    Ok(data)
+}
+
+/// `append_to_file` appends 16-bit samples to an existing WAVE PCM file.
+///
+/// The samples are written right after the existing audio data and the `data_size` and
+/// `total_size` fields are patched in place, so the existing audio data is never read into
+/// memory. Chunks following the audio data are read and moved behind the appended samples.
+///
+/// # Arguments
+///
+/// * `path` - A path to the WAV PCM file.
+/// * `samples` - Interleaved audio samples.
+///
+/// # Errors
+///
+/// If the file cannot be opened for reading and writing, is not 16-bit integer PCM, the
+/// samples do not make up whole frames or the file would grow beyond 4 GiB.
+///
+/// # Example
+///
+/// ```
+/// use wavepcm::Format;
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let path = std::env::temp_dir().join("wavepcm_append_to_file.wav");
+///     let path = path.to_str().unwrap();
+///
+///     Format::encode(vec![0u8; 8], 1, 16_000, 16)?.write(path)?;
+///     let before = std::fs::metadata(path)?.len();
+///
+///     wavepcm::append_to_file(path, &[1, 2, 3])?;
+///     assert_eq!(std::fs::metadata(path)?.len(), before + 6);
+///
+///     let decoding = Format::decode(path)?;
+///     decoding.check()?;
+///     assert_eq!(decoding.data[8..], [1, 0, 2, 0, 3, 0]);
+///
+///     // Three samples do not fill whole stereo frames.
+///     Format::encode(vec![0u8; 8], 2, 16_000, 16)?.write(path)?;
+///     assert!(wavepcm::append_to_file(path, &[1, 2, 3]).is_err());
+///     wavepcm::append_to_file(path, &[1, 2])?;
+///     Format::decode(path)?.check()?;
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn append_to_file<P: AsRef<Path>>(path: P, samples: &[i16]) -> Result<(), anyhow::Error> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+
+    let mut riff_header = [0_u8; 12];
+    file.read_exact(&mut riff_header)?;
+    let total_size = le_u32(&riff_header, 4);
+
+    let mut fmt = None;
+    let (data_start, data_size) = loop {
+        let mut chunk_header = [0_u8; 8];
+        if file.read_exact(&mut chunk_header).is_err() {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM file has no \"data\" chunk to append to."
+            ));
+        }
+
+        let size = le_u32(&chunk_header, 4);
+        let start = file.stream_position()?;
+        match &chunk_header[..4] {
+            b"fmt " if size >= 16 => {
+                // The sub-format of the extensible format ends 40 bytes into the chunk.
+                let mut fmt_chunk = vec![0_u8; usize::try_from(size.min(40))?];
+                file.read_exact(&mut fmt_chunk)?;
+                let fmt_code = u16::from_le_bytes([fmt_chunk[0], fmt_chunk[1]]);
+                let is_pcm = fmt_code == 1
+                    || (fmt_code == 0xFFFE
+                        && fmt_chunk.get(24..26) == Some(&[0x01, 0x00][..])
+                        && fmt_chunk.get(26..40) == Some(&SUBFORMAT_GUID_TAIL[..]));
+                fmt = Some((
+                    fmt_code,
+                    is_pcm,
+                    u16::from_le_bytes([fmt_chunk[12], fmt_chunk[13]]),
+                    u16::from_le_bytes([fmt_chunk[14], fmt_chunk[15]]),
+                ));
+            }
+            b"data" => break (start, size),
+            _ => {}
+        }
+        file.seek(SeekFrom::Start(
+            start + u64::from(size) + u64::from(size % 2),
+        ))?;
+    };
+
+    let Some((fmt_code, is_pcm, block_alignment, bits_per_sample)) = fmt else {
+        return Err(anyhow::anyhow!(
+            "WAVE PCM file has no \"fmt \" chunk before its \"data\" chunk."
+        ));
+    };
+    if !is_pcm {
+        return Err(anyhow::anyhow!(
+            "Appending requires integer PCM, got format code {fmt_code} instead."
+        ));
+    }
+    if bits_per_sample != 16 {
+        return Err(anyhow::anyhow!(
+            "Appending requires 16 bits per sample, got {bits_per_sample} instead."
+        ));
+    }
+
+    let appended: u32 = (samples.len() * 2).try_into()?;
+    if block_alignment == 0 || !appended.is_multiple_of(u32::from(block_alignment)) {
+        return Err(anyhow::anyhow!(
+            "Appending requires whole frames of {block_alignment} bytes, got {appended} bytes instead."
+        ));
+    }
+    let (Some(new_total_size), Some(new_data_size)) = (
+        total_size.checked_add(appended),
+        data_size.checked_add(appended),
+    ) else {
+        return Err(anyhow::anyhow!(
+            "WAVE PCM format cannot hold more than 4 GiB of audio data."
+        ));
+    };
+
+    // Chunks following the audio data are moved behind the appended samples.
+    let data_end = data_start + u64::from(data_size);
+    let mut trailer = Vec::new();
+    file.seek(SeekFrom::Start(data_end))?;
+    file.read_to_end(&mut trailer)?;
+
+    file.seek(SeekFrom::Start(data_end))?;
+    file.write_all(&i16_to_bytes(samples))?;
+    file.write_all(&trailer)?;
+
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&new_total_size.to_le_bytes())?;
+    file.seek(SeekFrom::Start(data_start - 4))?;
+    file.write_all(&new_data_size.to_le_bytes())?;
+
+    Ok(())
+}
+
+/// `decode_dir` decodes every WAV file in a directory.
+///
+/// Files with a `.wav` extension (in any case) are decoded in path order, each with its own
+/// result, so a single bad file does not abort the batch. Subdirectories are not visited, see
+/// [`decode_dir_recursive`].
+///
+/// # Arguments
+///
+/// * `dir` - A path to the directory.
+///
+/// # Errors
+///
+/// If the directory or one of its entries cannot be read.
+///
+/// # Example
+///
+/// ```
+/// use wavepcm::Format;
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let dir = std::env::temp_dir().join("wavepcm_decode_dir");
+///     std::fs::create_dir_all(&dir)?;
+///     Format::encode(vec![0u8; 16], 1, 16_000, 16)?.write(dir.join("a.wav").to_str().unwrap())?;
+///     std::fs::write(dir.join("b.wav"), b"not a wav file")?;
+///
+///     let results = wavepcm::decode_dir(&dir)?;
+///     assert_eq!(results.len(), 2);
+///     assert!(results[0].1.is_ok());
+///     assert!(results[1].1.is_err());
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_dir<P: AsRef<Path>>(dir: P) -> DecodeDirResult {
+    decode_dir_impl(dir.as_ref(), false)
+}
+
+/// `decode_dir_recursive` decodes every WAV file in a directory and its subdirectories.
+///
+/// See [`decode_dir`].
+///
+/// # Arguments
+///
+/// * `dir` - A path to the directory.
+///
+/// # Errors
+///
+/// If a directory or one of its entries cannot be read.
+#[cfg(feature = "std")]
+pub fn decode_dir_recursive<P: AsRef<Path>>(dir: P) -> DecodeDirResult {
+    decode_dir_impl(dir.as_ref(), true)
+}
+
+/// Result of [`decode_dir`], pairing every WAV file with its decoding.
+#[cfg(feature = "std")]
+pub type DecodeDirResult = Result<Vec<(PathBuf, Result<Format, anyhow::Error>)>, anyhow::Error>;
+
+// Decode every WAV file in a directory.
+//
+// # Arguments
+//
+// * `dir` - A path to the directory.
+// * `recursive` - Whether to visit subdirectories.
+//
+// # Errors
+//
+// If a directory or one of its entries cannot be read.
+#[cfg(feature = "std")]
+fn decode_dir_impl(dir: &Path, recursive: bool) -> DecodeDirResult {
+    let mut paths = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if recursive {
+                    dirs.push(path);
+                }
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+            {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let format = Format::decode_with_progress(&path, |_, _| {});
+            (path, format)
+        })
+        .collect())
+}
+
+/// Peak and RMS level of 16-bit audio data, see [`analyze_stream`] and [`Format::analyze`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[must_use]
+pub struct Analysis {
+    /// Largest absolute sample value.
+    pub peak: u16,
+    /// Root mean square of the samples.
+    pub rms: f64,
+    /// Number of samples across all channels.
+    pub sample_count: u64,
+}
+
+// Running totals behind an [`Analysis`].
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct AnalysisAccumulator {
+    peak: u16,
+    sum_squares: f64,
+    sample_count: u64,
+}
+
+#[cfg(feature = "std")]
+impl AnalysisAccumulator {
+    // Accumulate a block of little-endian 16-bit samples.
+    //
+    // # Arguments
+    //
+    // * `data` - Raw audio data.
+    fn add(&mut self, data: &[u8]) {
+        for pair in data.chunks_exact(2) {
+            let sample = i16::from_le_bytes([pair[0], pair[1]]);
+            self.peak = self.peak.max(sample.unsigned_abs());
+            self.sum_squares += f64::from(sample) * f64::from(sample);
+            self.sample_count += 1;
+        }
+    }
+
+    // Compute the analysis from the running totals.
+    #[allow(clippy::cast_precision_loss)]
+    fn finish(&self) -> Analysis {
+        let rms = if self.sample_count == 0 {
+            0.0
+        } else {
+            (self.sum_squares / self.sample_count as f64).sqrt()
+        };
+
+        Analysis {
+            peak: self.peak,
+            rms,
+            sample_count: self.sample_count,
+        }
+    }
+}
+
+/// `analyze_stream` computes the peak and RMS level of a 16-bit WAVE PCM stream.
+///
+/// The audio data is read in bounded blocks, so the samples are never held in memory at once.
+///
+/// # Arguments
+///
+/// * `reader` - A reader positioned at the start of the WAV PCM file.
+///
+/// # Errors
+///
+/// If the stream has no format or data chunk, or the audio data is not 16 bits per sample.
+///
+/// # Example
+///
+/// ```
+/// use wavepcm::Format;
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let data = (0..10_000_i16).flat_map(|x| (x - 5_000).to_le_bytes()).collect();
+///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+///
+///     let analysis = wavepcm::analyze_stream(&encoding.to_bytes()?[..])?;
+///     assert_eq!(analysis, encoding.analyze()?);
+///     assert_eq!(analysis.peak, 5_000);
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn analyze_stream<R: Read>(reader: R) -> Result<Analysis, anyhow::Error> {
+    let mut decoder = Decoder::new(reader)?;
+    let bits_per_sample = decoder.bits_per_sample();
+    if bits_per_sample != 16 {
+        return Err(anyhow::anyhow!(
+            "Operation requires 16 bits per sample, got {bits_per_sample} instead."
+        ));
+    }
+
+    let mut accumulator = AnalysisAccumulator::default();
+    let mut buf = vec![0_u8; 1 << 16];
+    loop {
+        let nbytes = decoder.read_data(&mut buf)?;
+        if nbytes == 0 {
+            break;
+        }
+        accumulator.add(&buf[..nbytes]);
+    }
+
+    Ok(accumulator.finish())
+}
+
+/// `resample_stream` converts a WAVE PCM stream to another sampling rate without holding it in
+/// memory.
+///
+/// The audio data is read and written in blocks, keeping the last input frame of a block so
+/// interpolation carries across block boundaries. The output matches [`Format::resample`] on the
+/// same file, except that chunks other than the format and audio data are not copied.
+///
+/// # Arguments
+///
+/// * `reader` - A reader positioned at the start of the WAV PCM file.
+/// * `writer` - A writer receiving the resampled WAV PCM file.
+/// * `sampling_rate` - Sampling rate of the resampled audio data.
+///
+/// # Errors
+///
+/// If the stream has no format or data chunk or ends early, either sampling rate is 0, the audio
+/// data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM, or writing fails.
+///
+/// # Example
+///
+/// ```
+/// use wavepcm::Format;
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let data = (0..800_000_u32).flat_map(|n| (n.wrapping_mul(7_919) as u16).to_le_bytes()).collect();
+///     let encoding = Format::encode(data, 2, 48_000, 16)?;
+///
+///     for sampling_rate in [44_100, 96_000] {
+///         let mut output = Vec::new();
+///         wavepcm::resample_stream(&encoding.to_bytes()?[..], &mut output, sampling_rate)?;
+///         assert_eq!(output, encoding.resample(sampling_rate)?.to_bytes()?);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[allow(clippy::cast_possible_truncation)]
+pub fn resample_stream<R: Read, W: Write>(
+    reader: R,
+    mut writer: W,
+    sampling_rate: u32,
+) -> Result<(), anyhow::Error> {
+    let mut decoder = Decoder::new(reader)?;
+    let source_rate = decoder.sampling_rate();
+    if source_rate == 0 || sampling_rate == 0 {
+        return Err(anyhow::anyhow!(
+            "Resampling requires non-zero sampling rates, got {source_rate} and {sampling_rate} instead."
+        ));
+    }
+
+    // The header holds no audio data, so this only checks the sample format.
+    let input = decoder.header()?;
+    input.normalized_f32()?;
+    let float = input.sample_fmt_code() == 3;
+
+    let num_channels = usize::from(decoder.num_channels().max(1));
+    let width = usize::from(decoder.bits_per_sample() / 8);
+    let frame_size = num_channels * width;
+    let frame_count: usize = decoder.frame_count().try_into()?;
+    let output_frames: usize =
+        resampled_frames(decoder.frame_count(), source_rate, sampling_rate).try_into()?;
+
+    let mut output = input.with_normalized_f32(&[], sampling_rate)?;
+    let data_size = u32::try_from(output_frames * frame_size)?;
+    output.data_size = data_size.to_le_bytes();
+    output.total_size = (u32::try_from(output.header()?.len() - 8)? + data_size).to_le_bytes();
+    writer.write_all(&output.header()?)?;
+
+    let mut block = vec![0_u8; READ_BLOCK / frame_size * frame_size];
+    let mut window: Vec<f32> = Vec::new();
+    let mut window_start = 0;
+    let mut data = Vec::with_capacity(WRITE_BLOCK + frame_size);
+    for frame in 0..output_frames {
+        let (idx, frac) = resample_position(frame, source_rate, sampling_rate);
+        let next = (idx + 1).min(frame_count - 1);
+
+        // Frames before `idx` are no longer needed, the rest stays for the next block.
+        while window_start + window.len() / num_channels <= next {
+            let consumed = (idx - window_start).min(window.len() / num_channels);
+            window.drain(..consumed * num_channels);
+            window_start += consumed;
+
+            let nbytes = decoder.read_data(&mut block)?;
+            if nbytes == 0 {
+                return Err(anyhow::anyhow!(
+                    "WAVE PCM stream ended after {window_start} of {frame_count} frames."
+                ));
+            }
+            window.extend(
+                block[..nbytes]
+                    .chunks_exact(width)
+                    .map(|bytes| sample_to_f32(bytes, float)),
+            );
+        }
+
+        let current = &window[(idx - window_start) * num_channels..];
+        let following = &window[(next - window_start) * num_channels..];
+        for channel in 0..num_channels {
+            let current = f64::from(current[channel]);
+            let following = f64::from(following[channel]);
+            f32_to_sample(
+                (current + (following - current) * frac) as f32,
+                width,
+                float,
+                &mut data,
+            );
+        }
+
+        if data.len() >= WRITE_BLOCK {
+            writer.write_all(&data)?;
+            data.clear();
+        }
+    }
+    writer.write_all(&data)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Streaming WAVE PCM decoder yielding one frame of raw audio data at a time.
+///
+/// Only the header is parsed up front, the audio data is read as the decoder is iterated, so
+/// files larger than memory can be processed.
+///
+/// # Example
+///
+/// ```
+/// use wavepcm::{Decoder, Format};
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let data = [1_i16, 2, 3, 4].iter().flat_map(|x| x.to_le_bytes()).collect();
+///     let bytes = Format::encode(data, 2, 16_000, 16)?.to_bytes()?;
+///
+///     let decoder = Decoder::new(&bytes[..])?;
+///     assert_eq!(decoder.frame_count(), 2);
+///     let frames: Vec<Vec<u8>> = decoder.collect();
+///     assert_eq!(frames, vec![vec![1, 0, 2, 0], vec![3, 0, 4, 0]]);
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub struct Decoder<R> {
+    reader: R,
+    num_channels: u16,
+    sampling_rate: u32,
+    bits_per_sample: u16,
+    fmt_chunk: Vec<u8>,
+    data_size: u32,
+    position: u32,
+}
+
+#[cfg(feature = "std")]
+impl<R> Decoder<R>
+where
+    R: Read,
+{
+    /// `new` parses the header of a WAVE PCM stream up to the start of the audio data.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader positioned at the start of the WAV PCM file.
+    ///
+    /// # Errors
+    ///
+    /// If the stream is not a RIFF WAVE file, has no format chunk before its data chunk, or ends
+    /// within its format chunk.
+    pub fn new(mut reader: R) -> Result<Self, anyhow::Error> {
+        let mut riff_header = [0_u8; 12];
+        reader.read_exact(&mut riff_header)?;
+        if &riff_header[..4] != b"RIFF" || &riff_header[8..] != b"WAVE" {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires strings \"RIFF\" and \"WAVE\" as bytes 1 - 4 and 9 - 12."
+            ));
+        }
+
+        let mut fmt_chunk = None;
+        let data_size = loop {
+            let mut chunk_header = [0_u8; 8];
+            if reader.read_exact(&mut chunk_header).is_err() {
+                return Err(anyhow::anyhow!("WAVE PCM stream has no \"data\" chunk."));
+            }
+            let size = le_u32(&chunk_header, 4);
+
+            match &chunk_header[..4] {
+                b"data" => break size,
+                b"fmt " => {
+                    // Grow the buffer as bytes arrive, so a corrupt size does not allocate up front.
+                    let mut chunk = Vec::new();
+                    (&mut reader)
+                        .take(u64::from(size))
+                        .read_to_end(&mut chunk)?;
+                    if chunk.len() as u64 != u64::from(size) {
+                        return Err(anyhow::anyhow!(
+                            "WAVE PCM stream has a \"fmt \" chunk of {size} bytes, got {} bytes instead.",
+                            chunk.len()
+                        ));
+                    }
+                    fmt_chunk = Some(chunk);
+                }
+                _ => {
+                    std::io::copy(
+                        &mut (&mut reader).take(u64::from(size)),
+                        &mut std::io::sink(),
+                    )?;
+                }
+            }
+            if size % 2 == 1 {
+                reader.read_exact(&mut [0_u8; 1])?;
+            }
+        };
+
+        let Some(fmt_chunk) = fmt_chunk.filter(|chunk| chunk.len() >= 16) else {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM stream has no \"fmt \" chunk before its \"data\" chunk."
+            ));
+        };
+
+        Ok(Decoder {
+            reader,
+            num_channels: u16::from_le_bytes([fmt_chunk[2], fmt_chunk[3]]),
+            sampling_rate: le_u32(&fmt_chunk, 4),
+            bits_per_sample: u16::from_le_bytes([fmt_chunk[14], fmt_chunk[15]]),
+            fmt_chunk,
+            data_size,
+            position: 0,
+        })
+    }
+
+    /// `num_channels` returns the number of channels in the audio data.
+    #[must_use]
+    pub fn num_channels(&self) -> u16 {
+        self.num_channels
+    }
+
+    /// `sampling_rate` returns the sampling rate in the audio data.
+    #[must_use]
+    pub fn sampling_rate(&self) -> u32 {
+        self.sampling_rate
+    }
+
+    /// `bits_per_sample` returns the bits per sample in the audio data.
+    #[must_use]
+    pub fn bits_per_sample(&self) -> u16 {
+        self.bits_per_sample
+    }
+
+    /// `frame_count` returns the number of complete frames declared by the data chunk.
+    #[must_use]
+    pub fn frame_count(&self) -> u64 {
+        match u64::from(self.frame_size()) {
+            0 => 0,
+            frame_size => u64::from(self.data_size) / frame_size,
+        }
+    }
+
+    // Return a file without audio data holding the format chunk of the stream.
+    //
+    // # Errors
+    //
+    // If the format chunk cannot be encoded, see `Format::encode`.
+    fn header(&self) -> Result<Format, anyhow::Error> {
+        let mut format = Format::encode(
+            Vec::new(),
+            self.num_channels,
+            self.sampling_rate,
+            self.bits_per_sample,
+        )?;
+        format.fmt_chunk_size = u32::try_from(self.fmt_chunk.len())?.to_le_bytes();
+        format.fmt_code = [self.fmt_chunk[0], self.fmt_chunk[1]];
+        format.fmt_extension = self.fmt_chunk[16..].to_vec();
+        Ok(format)
+    }
+
+    // Return the number of bytes in a frame.
+    fn frame_size(&self) -> u32 {
+        u32::from(self.num_channels) * u32::from(self.bits_per_sample.div_ceil(8))
+    }
+
+    // Read raw audio data without going past the end of the data chunk.
+    //
+    // # Arguments
+    //
+    // * `buf` - Output buffer.
+    //
+    // # Errors
+    //
+    // If reading from the underlying reader fails.
+    fn read_data(&mut self, buf: &mut [u8]) -> Result<usize, anyhow::Error> {
+        let len = buf.len().min((self.data_size - self.position) as usize);
+        let nbytes = IoSource(&mut self.reader).fill(&mut buf[..len]);
+        self.position += u32::try_from(nbytes)?;
+        Ok(nbytes)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> Decoder<R>
+where
+    R: Read + Seek,
+{
+    /// `seek_to_frame` moves the decoder to the given frame.
+    ///
+    /// Frames past the end of the audio data are clamped, so the decoder is exhausted. The reader
+    /// is sought relative to its current position, so the stream need not start at offset 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Index of the frame to read next.
+    ///
+    /// # Errors
+    ///
+    /// If seeking the underlying reader fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::{Cursor, Seek, SeekFrom};
+    /// use wavepcm::{Decoder, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = (0..4_000_i16).flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let mut decoder = Decoder::new(Cursor::new(encoding.to_bytes()?))?;
+    ///     decoder.seek_to_frame(1_000)?;
+    ///     let frame = decoder.next().unwrap();
+    ///     assert_eq!(i16::from_le_bytes([frame[0], frame[1]]), encoding.sample_at_i16(1_000, 0)?);
+    ///     assert_eq!(i16::from_le_bytes([frame[2], frame[3]]), encoding.sample_at_i16(1_000, 1)?);
+    ///
+    ///     decoder.seek_to_frame(5_000)?;
+    ///     assert!(decoder.next().is_none());
+    ///
+    ///     // A file embedded after 3 bytes of another stream.
+    ///     let mut stream = Cursor::new([&[7u8; 3][..], &encoding.to_bytes()?].concat());
+    ///     stream.seek(SeekFrom::Start(3))?;
+    ///     let mut decoder = Decoder::new(stream)?;
+    ///     decoder.seek_to_frame(1_000)?;
+    ///     assert_eq!(decoder.next().unwrap(), frame);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn seek_to_frame(&mut self, frame: u64) -> Result<(), anyhow::Error> {
+        let offset = frame.min(self.frame_count()) * u64::from(self.frame_size());
+        let delta = i64::try_from(offset)? - i64::from(self.position);
+        self.reader.seek(SeekFrom::Current(delta))?;
+        self.position = offset.try_into()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> Iterator for Decoder<R>
+where
+    R: Read,
+{
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut frame = vec![0_u8; self.frame_size() as usize];
+        match self.read_data(&mut frame) {
+            Ok(nbytes) if nbytes == frame.len() && nbytes > 0 => Some(frame),
+            _ => None,
+        }
+    }
+}
+
+/// WAVE PCM file whose audio data stays memory-mapped instead of being read into a [`Vec`].
+///
+/// Returned by [`Format::decode_mmap`], requires the `mmap` feature. Only the format chunk is
+/// parsed, metadata chunks are not. The accessors mirror those of [`Format`] and read straight
+/// from the mapped file.
+#[cfg(feature = "mmap")]
+pub struct MmapFormat {
+    header: Format,
+    map: memmap2::Mmap,
+    data: core::ops::Range<usize>,
+}
+
+#[cfg(feature = "mmap")]
+impl MmapFormat {
+    /// `header` returns the file without its audio data, holding the format chunk.
+    pub fn header(&self) -> &Format {
+        &self.header
+    }
+
+    /// `data` returns the mapped audio data.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.map[self.data.clone()]
+    }
+
+    /// `frame_count` returns the number of complete frames in the audio data, see
+    /// [`Format::frame_count`].
+    #[must_use]
+    pub fn frame_count(&self) -> u64 {
+        self.header.frames_in(self.data())
+    }
+
+    /// `sample_at_i16` reads a single 16-bit sample, see [`Format::sample_at_i16`].
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Index of the frame.
+    /// * `channel` - Index of the channel within the frame.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample, or either index is out of range.
+    pub fn sample_at_i16(&self, frame: u64, channel: u16) -> Result<i16, anyhow::Error> {
+        self.header.sample_at_i16_in(self.data(), frame, channel)
+    }
+
+    /// `deinterleave_into_i16` de-interleaves the 16-bit samples into caller-owned buffers, see
+    /// [`Format::deinterleave_into_i16`].
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - One buffer per channel.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample, or if `out` does not hold one buffer per
+    /// channel.
+    pub fn deinterleave_into_i16(&self, out: &mut [Vec<i16>]) -> Result<(), anyhow::Error> {
+        self.header.deinterleave_into_i16_in(self.data(), out)
+    }
+
+    /// `to_format` copies the mapped audio data into an owned [`Format`].
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    pub fn to_format(&self) -> Result<Format, anyhow::Error> {
+        let mut format = Format {
+            data_size: u32::try_from(self.data.len())?.to_le_bytes(),
+            data: self.data().to_vec(),
+            ..self.header.clone()
+        };
+        format.sync_total_size()?;
+        Ok(format)
+    }
+}
+
+// Scale a little-endian PCM sample of 8, 16, 24 or 32 bits to the full 32-bit range.
+//
+// # Arguments
+//
+// * `bytes` - Bytes of a single sample, 8-bit samples are unsigned.
+fn sample_to_i32(bytes: &[u8]) -> i32 {
+    match *bytes {
+        [b0] => (i32::from(b0) - 128) << 24,
+        [b0, b1] => i32::from(i16::from_le_bytes([b0, b1])) << 16,
+        [b0, b1, b2] => i32::from_le_bytes([0, b0, b1, b2]),
+        [b0, b1, b2, b3] => i32::from_le_bytes([b0, b1, b2, b3]),
+        _ => unreachable!("samples are 1 to 4 bytes wide"),
+    }
+}
+
+// Segment end points of the G.711 mu-law encoder, in 14-bit magnitudes.
+const MULAW_SEGMENTS: [i32; 8] = [0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF, 0x1FFF];
+
+// Segment end points of the G.711 A-law encoder, in 13-bit magnitudes.
+const ALAW_SEGMENTS: [i32; 8] = [0x1F, 0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF];
+
+// Compress a 16-bit sample to a G.711 mu-law byte.
+//
+// # Arguments
+//
+// * `sample` - Audio sample.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn linear_to_mulaw(sample: i16) -> u8 {
+    let mut pcm = i32::from(sample) >> 2;
+    let mask = if pcm < 0 {
+        pcm = -pcm;
+        0x7F
+    } else {
+        0xFF
+    };
+    pcm = pcm.min(8_159) + 0x21;
+
+    match MULAW_SEGMENTS.iter().position(|&end| pcm <= end) {
+        Some(segment) => ((segment << 4) as u8 | ((pcm >> (segment + 1)) & 0x0F) as u8) ^ mask,
+        None => 0x7F ^ mask,
+    }
+}
+
+// Compress a 16-bit sample to a G.711 A-law byte.
+//
+// # Arguments
+//
+// * `sample` - Audio sample.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn linear_to_alaw(sample: i16) -> u8 {
+    let mut pcm = i32::from(sample) >> 3;
+    let mask = if pcm >= 0 {
+        0xD5
+    } else {
+        pcm = -pcm - 1;
+        0x55
+    };
+
+    match ALAW_SEGMENTS.iter().position(|&end| pcm <= end) {
+        Some(segment) => {
+            let shift = if segment < 2 { 1 } else { segment };
+            ((segment << 4) as u8 | ((pcm >> shift) & 0x0F) as u8) ^ mask
+        }
+        None => 0x7F ^ mask,
+    }
+}
+
+// Expand a G.711 mu-law byte to a 16-bit sample.
+//
+// # Arguments
+//
+// * `byte` - Compressed sample.
+#[cfg(feature = "std")]
+fn mulaw_to_linear(byte: u8) -> i16 {
+    let byte = !byte;
+    let magnitude = ((i16::from(byte & 0x0F) << 3) + 0x84) << ((byte & 0x70) >> 4);
+    if byte & 0x80 == 0 {
+        magnitude - 0x84
+    } else {
+        0x84 - magnitude
+    }
+}
+
+// Expand a G.711 A-law byte to a 16-bit sample.
+//
+// # Arguments
+//
+// * `byte` - Compressed sample.
+#[cfg(feature = "std")]
+fn alaw_to_linear(byte: u8) -> i16 {
+    let byte = byte ^ 0x55;
+    let segment = (byte & 0x70) >> 4;
+    let mantissa = i16::from(byte & 0x0F) << 4;
+    let magnitude = match segment {
+        0 => mantissa + 8,
+        _ => (mantissa + 0x108) << (segment - 1),
+    };
+    if byte & 0x80 == 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+// Lowpass filter interleaved samples below the Nyquist frequency of a lower sampling rate.
+//
+// Two cascaded biquads keep aliasing from above the new Nyquist frequency low.
+//
+// # Arguments
+//
+// * `samples` - Interleaved audio samples.
+// * `num_channels` - Number of channels in the audio data.
+// * `lowpass` - Filter from `BiquadCoeffs::anti_alias`.
+#[cfg(feature = "std")]
+fn anti_alias(samples: &[f32], num_channels: usize, lowpass: &BiquadCoeffs) -> Vec<f64> {
+    let samples: Vec<f64> = samples.iter().map(|&sample| f64::from(sample)).collect();
+    lowpass.apply_f64(&lowpass.apply_f64(&samples, num_channels), num_channels)
+}
+
+// Resample interleaved samples by linear interpolation.
+//
+// The output has `frame_count * sampling_rate / source_rate` frames (rounded down).
+//
+// # Arguments
+//
+// * `samples` - Interleaved audio samples.
+// * `num_channels` - Number of channels in the audio data.
+// * `source_rate` - Sampling rate of the samples, must not be 0.
+// * `sampling_rate` - Sampling rate of the output.
+//
+// # Errors
+//
+// If the value cannot fit when performing type conversion.
+#[cfg(feature = "std")]
+#[allow(clippy::cast_possible_truncation)]
+fn interpolate_linear(
+    samples: &[f32],
+    num_channels: usize,
+    source_rate: u32,
+    sampling_rate: u32,
+) -> Result<Vec<f32>, anyhow::Error> {
+    let frame_count = samples.len() / num_channels;
+    let output_frames: usize =
+        resampled_frames(frame_count as u64, source_rate, sampling_rate).try_into()?;
+
+    let mut output = Vec::with_capacity(output_frames * num_channels);
+    for frame in 0..output_frames {
+        let (idx, frac) = resample_position(frame, source_rate, sampling_rate);
+        let next = (idx + 1).min(frame_count - 1);
+        for channel in 0..num_channels {
+            let current = f64::from(samples[idx * num_channels + channel]);
+            let following = f64::from(samples[next * num_channels + channel]);
+            output.push((current + (following - current) * frac) as f32);
+        }
+    }
+    Ok(output)
+}
+
+// Count the frames of audio data once resampled, rounded down.
+//
+// # Arguments
+//
+// * `frame_count` - Number of frames at the source rate.
+// * `source_rate` - Sampling rate of the input, 0 yields 0 frames.
+// * `sampling_rate` - Sampling rate of the output.
+fn resampled_frames(frame_count: u64, source_rate: u32, sampling_rate: u32) -> u64 {
+    (u128::from(frame_count) * u128::from(sampling_rate))
+        .checked_div(u128::from(source_rate))
+        .map_or(0, |frames| u64::try_from(frames).unwrap_or(u64::MAX))
+}
+
+// Locate an output frame of a resampler between two input frames.
+//
+// The position `frame * source_rate / sampling_rate` is computed as an exact fraction rather
+// than by accumulating a float step, so the phase does not drift over long files.
+//
+// # Arguments
+//
+// * `frame` - Index of the output frame.
+// * `source_rate` - Sampling rate of the input.
+// * `sampling_rate` - Sampling rate of the output, must not be 0.
+#[cfg(feature = "std")]
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn resample_position(frame: usize, source_rate: u32, sampling_rate: u32) -> (usize, f64) {
+    let numerator = frame as u128 * u128::from(source_rate);
+    let denominator = u128::from(sampling_rate);
+    let idx = (numerator / denominator) as usize;
+    let frac = (numerator % denominator) as f64 / f64::from(sampling_rate);
+    (idx, frac)
+}
+
+// Lookup table of the reflected CRC-32 (IEEE 802.3) polynomial, one entry per byte value.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0_u32; 256];
+    let mut value = 0_u32;
+    while value < 256 {
+        let mut crc = value;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[value as usize] = crc;
+        value += 1;
+    }
+    table
+};
+
+// Compute the CRC-32 (IEEE 802.3) checksum used by zip, gzip and PNG.
+//
+// # Arguments
+//
+// * `bytes` - Bytes to checksum.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(u32::MAX, |crc, &byte| {
+        CRC32_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+// Convert a little-endian sample to a value normalized to [-1, 1).
+//
+// # Arguments
+//
+// * `bytes` - Bytes of a single sample, 8-bit samples are unsigned.
+// * `float` - Whether the sample is 32-bit float rather than integer PCM.
+#[allow(clippy::cast_possible_truncation)]
+fn sample_to_f32(bytes: &[u8], float: bool) -> f32 {
+    match *bytes {
+        [b0, b1, b2, b3] if float => f32::from_le_bytes([b0, b1, b2, b3]),
+        _ => (f64::from(sample_to_i32(bytes)) / 2_147_483_648.0) as f32,
+    }
+}
+
+// Replace a NaN sample with 0.0 and clamp an infinite sample to ±1.0.
+//
+// # Arguments
+//
+// * `sample` - Normalized sample.
+fn sanitize_f32(sample: f32) -> f32 {
+    if sample.is_nan() {
+        0.0
+    } else if sample.is_infinite() {
+        sample.clamp(-1.0, 1.0)
+    } else {
+        sample
+    }
+}
+
+// Append a normalized sample as little-endian integer or float PCM.
+//
+// # Arguments
+//
+// * `sample` - Sample normalized to [-1, 1).
+// * `bytes_per_sample` - Width of integer samples, float samples are always 4 bytes.
+// * `float` - Whether to write 32-bit float rather than integer PCM.
+// * `buf` - Output buffer.
+#[cfg(feature = "std")]
+fn f32_to_sample(sample: f32, bytes_per_sample: usize, float: bool, buf: &mut Vec<u8>) {
+    if float {
+        buf.extend_from_slice(&sample.to_le_bytes());
+    } else {
+        i32_to_sample(
+            saturate_i32(f64::from(sample) * 2_147_483_648.0),
+            bytes_per_sample,
+            buf,
+        );
+    }
+}
+
+// Convert a duration to a number of frames, rounding down, without floating point error.
+//
+// # Arguments
+//
+// * `duration` - Duration to convert.
+// * `sampling_rate` - Sampling rate in frames per second.
+fn duration_to_frames(duration: core::time::Duration, sampling_rate: u32) -> u64 {
+    let frames = duration.as_nanos() * u128::from(sampling_rate) / 1_000_000_000;
+    u64::try_from(frames).unwrap_or(u64::MAX)
+}
+
+// Write a sample scaled to the full 32-bit range as a little-endian PCM sample.
+//
+// # Arguments
+//
+// * `sample` - Audio sample.
+// * `bytes_per_sample` - Width of the written sample, 8-bit samples are unsigned.
+// * `buf` - Output buffer.
+#[cfg(feature = "std")]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn i32_to_sample(sample: i32, bytes_per_sample: usize, buf: &mut Vec<u8>) {
+    let bytes = sample.to_le_bytes();
+    match bytes_per_sample {
+        1 => buf.push(((sample >> 24) + 128) as u8),
+        _ => buf.extend_from_slice(&bytes[4 - bytes_per_sample..]),
+    }
+}
+
+// Deterministic xorshift pseudo-random number generator.
+#[cfg(feature = "std")]
+struct XorShift(u64);
+
+#[cfg(feature = "std")]
+impl XorShift {
+    // Create a generator, a zero seed is replaced since xorshift never leaves zero.
+    //
+    // # Arguments
+    //
+    // * `seed` - Generator seed.
+    fn new(seed: u64) -> Self {
+        XorShift(if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        })
+    }
+
+    // Return the next value uniformly distributed in [0, 1).
+    #[allow(clippy::cast_precision_loss)]
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+/// Dither applied when reducing the bit depth.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dither {
+    /// Plain truncation.
+    None,
+    /// Triangular probability density function dither of one least significant bit, generated
+    /// from the given seed so the output is reproducible.
+    Triangular {
+        /// Random number generator seed.
+        seed: u64,
+    },
+}
+
+/// Chunk that is not modeled by [`Format`], kept verbatim so it survives a round trip.
+///
+/// # Example
+///
+/// ```
+/// use wavepcm::Format;
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let plain = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+///     let list = b"LIST\x05\x00\x00\x00INFOa\x00";
+///     let cue = b"cue \x04\x00\x00\x00\x00\x00\x00\x00";
+///
+///     let mut bytes = [&plain[..36], list, &plain[36..], cue].concat();
+///     let total_size = (bytes.len() as u32 - 8).to_le_bytes();
+///     bytes[4..8].copy_from_slice(&total_size);
+///
+///     let decoding = Format::decode_bytes(&bytes)?;
+///     assert_eq!(decoding.leading_chunks[0].tag, *b"LIST");
+///     assert_eq!(decoding.trailing_chunks[0].tag, *b"cue ");
+///     assert_eq!(decoding.to_bytes()?, bytes);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chunk {
+    /// Chunk tag (e.g. "LIST").
+    pub tag: [u8; 4],
+    /// Chunk contents without the tag, size and alignment byte.
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    // Append the chunk to a buffer, including the tag, size and alignment byte.
+    //
+    // # Arguments
+    //
+    // * `bytes` - Output buffer.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn write_to(&self, bytes: &mut Vec<u8>) -> Result<(), anyhow::Error> {
+        let size: u32 = self.data.len().try_into()?;
+        bytes.extend_from_slice(&self.tag);
+        bytes.extend_from_slice(&size.to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        if self.data.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        Ok(())
+    }
+
+    // Return the chunk as bytes, including the tag, size and alignment byte.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn to_bytes(&self) -> Result<Vec<u8>, anyhow::Error> {
+        let mut bytes = Vec::with_capacity(self.data.len() + 9);
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+// Kind of a chunk, recording the chunk order of a decoded file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ChunkKind {
+    // The format chunk.
+    Fmt,
+    // The data chunk.
+    Data,
+    // The `bext` chunk modeled by `broadcast_info`.
+    Bext,
+    // The `smpl` chunk modeled by `sampler` and `sample_loops`.
+    Smpl,
+    // The `inst` chunk modeled by `instrument`.
+    Inst,
+    // The `cue ` chunk modeled by `cue_points`.
+    Cue,
+    // The `LIST` `adtl` chunk modeled by the labels of `cue_points`.
+    Labels,
+    // A chunk kept verbatim.
+    Unknown,
+}
+
+// Sort serialized chunks into the recorded chunk order of a decoded file.
+//
+// The n-th chunk of a kind takes the position of the n-th chunk of that kind in the recorded
+// order. A chunk missing from it stays right after the chunk preceding it by default.
+//
+// # Arguments
+//
+// * `chunks` - Serialized chunks and their kinds, in the order written by default.
+// * `chunk_order` - Recorded kinds of the chunks.
+fn in_chunk_order(chunks: Vec<(ChunkKind, Vec<u8>)>, chunk_order: &[ChunkKind]) -> Vec<Vec<u8>> {
+    let mut seen = Vec::with_capacity(chunks.len());
+    let mut position = 0;
+    let mut keyed: Vec<(usize, Vec<u8>)> = chunks
+        .into_iter()
+        .map(|(kind, chunk)| {
+            let nth = seen.iter().filter(|&&other| other == kind).count();
+            seen.push(kind);
+            if let Some((recorded, _)) = chunk_order
+                .iter()
+                .enumerate()
+                .filter(|&(_, &other)| other == kind)
+                .nth(nth)
+            {
+                position = recorded;
+            }
+            (position, chunk)
+        })
+        .collect();
+
+    keyed.sort_by_key(|&(position, _)| position);
+    keyed.into_iter().map(|(_, chunk)| chunk).collect()
+}
+
+// Parse the entries of a `LIST` chunk of type `INFO`.
+//
+// # Arguments
+//
+// * `data` - Contents of the `LIST` chunk.
+fn parse_info(data: &[u8]) -> Option<Vec<([u8; 4], Vec<u8>)>> {
+    let mut rest = data.strip_prefix(b"INFO")?;
+    let mut entries = Vec::new();
+    while !rest.is_empty() {
+        if rest.len() < 8 {
+            return None;
+        }
+        let key = [rest[0], rest[1], rest[2], rest[3]];
+        let size = le_u32(rest, 4) as usize;
+        let value = rest.get(8..8 + size)?;
+        entries.push((key, value.to_vec()));
+        rest = rest.get(8 + size + size % 2..).unwrap_or_default();
+    }
+    Some(entries)
+}
+
+// Build the contents of a `LIST` chunk of type `INFO`.
+//
+// # Arguments
+//
+// * `entries` - Keys and values, values are written verbatim.
+//
+// # Errors
+//
+// If the value cannot fit when performing type conversion.
+fn build_info(entries: &[([u8; 4], Vec<u8>)]) -> Result<Vec<u8>, anyhow::Error> {
+    let mut data = b"INFO".to_vec();
+    for (key, value) in entries {
+        Chunk {
+            tag: *key,
+            data: value.clone(),
+        }
+        .write_to(&mut data)?;
+    }
+    Ok(data)
+}
+
+/// Broadcast Wave Format metadata from the `bext` chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BroadcastInfo {
+    /// Description of the sound sequence (up to 256 bytes).
+    pub description: String,
+    /// Name of the originator (up to 32 bytes).
+    pub originator: String,
+    /// Reference of the originator (up to 32 bytes).
+    pub originator_reference: String,
+    /// Date of creation ("yyyy-mm-dd").
+    pub origination_date: String,
+    /// Time of creation ("hh-mm-ss").
+    pub origination_time: String,
+    /// Number of samples since midnight of the first sample.
+    pub time_reference: u64,
+    /// Every field following `time_reference` (version, UMID, loudness and coding history),
+    /// kept verbatim. An empty value is written as a version 0 chunk without coding history.
+    pub remainder: Vec<u8>,
+}
+
+impl BroadcastInfo {
+    // Parse a `bext` chunk body.
+    //
+    // # Arguments
+    //
+    // * `body` - Contents of the chunk without the tag and size.
+    fn parse(body: &[u8]) -> Option<BroadcastInfo> {
+        if body.len() < 346 {
+            return None;
+        }
+
+        let text = |range: core::ops::Range<usize>| {
+            let field = &body[range];
+            let end = field
+                .iter()
+                .position(|&byte| byte == 0)
+                .unwrap_or(field.len());
+            String::from_utf8_lossy(&field[..end]).into_owned()
+        };
+
+        Some(BroadcastInfo {
+            description: text(0..256),
+            originator: text(256..288),
+            originator_reference: text(288..320),
+            origination_date: text(320..330),
+            origination_time: text(330..338),
+            time_reference: u64::from(le_u32(body, 338)) | u64::from(le_u32(body, 342)) << 32,
+            remainder: body[346..].to_vec(),
+        })
+    }
+
+    // Serialize into a `bext` chunk.
+    fn chunk(&self) -> Chunk {
+        let mut data = Vec::with_capacity(602);
+        for (text, size) in [
+            (&self.description, 256),
+            (&self.originator, 32),
+            (&self.originator_reference, 32),
+            (&self.origination_date, 10),
+            (&self.origination_time, 8),
+        ] {
+            let bytes = &text.as_bytes()[..text.len().min(size)];
+            data.extend_from_slice(bytes);
+            data.resize(data.len() + size - bytes.len(), 0);
+        }
+        data.extend_from_slice(&self.time_reference.to_le_bytes());
+        if self.remainder.is_empty() {
+            data.resize(602, 0);
+        } else {
+            data.extend_from_slice(&self.remainder);
+        }
+
+        Chunk {
+            tag: *b"bext",
+            data,
+        }
+    }
+}
+
+/// Playback parameters from the instrument (`inst`) chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instrument {
+    /// MIDI note that plays the audio data at its original pitch.
+    pub unshifted_note: u8,
+    /// Pitch correction in cents (-50 - 50).
+    pub fine_tune: i8,
+    /// Gain in decibels.
+    pub gain: i8,
+    /// Lowest MIDI note of the key range.
+    pub low_note: u8,
+    /// Highest MIDI note of the key range.
+    pub high_note: u8,
+    /// Lowest MIDI velocity of the velocity range.
+    pub low_velocity: u8,
+    /// Highest MIDI velocity of the velocity range.
+    pub high_velocity: u8,
+}
+
+impl Instrument {
+    // Parse an `inst` chunk body.
+    //
+    // # Arguments
+    //
+    // * `body` - Contents of the chunk without the tag and size.
+    fn parse(body: &[u8]) -> Option<Instrument> {
+        let [unshifted_note, fine_tune, gain, low_note, high_note, low_velocity, high_velocity] =
+            *body.get(..7)?
+        else {
+            return None;
+        };
+
+        Some(Instrument {
+            unshifted_note,
+            fine_tune: i8::from_le_bytes([fine_tune]),
+            gain: i8::from_le_bytes([gain]),
+            low_note,
+            high_note,
+            low_velocity,
+            high_velocity,
+        })
+    }
+
+    // Serialize the instrument into an `inst` chunk.
+    fn chunk(self) -> Chunk {
+        Chunk {
+            tag: *b"inst",
+            data: vec![
+                self.unshifted_note,
+                self.fine_tune.to_le_bytes()[0],
+                self.gain.to_le_bytes()[0],
+                self.low_note,
+                self.high_note,
+                self.low_velocity,
+                self.high_velocity,
+            ],
+        }
+    }
+}
+
+/// Cue point from the `cue ` chunk, with its text label from the `LIST` `adtl` chunk.
+///
+/// A `LIST` `adtl` chunk following the `cue ` chunk and holding only labels of its cue points is
+/// modeled by the labels, any other associated data chunk is kept verbatim.
+///
+/// # Example
+///
+/// ```
+/// use wavepcm::{CuePoint, Format};
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let plain = Format::encode(vec![0u8; 400], 1, 16_000, 16)?.to_bytes()?;
+///     let mut cue = b"cue \x1C\x00\x00\x00\x01\x00\x00\x00\x07\x00\x00\x00".to_vec();
+///     cue.extend_from_slice(&100_u32.to_le_bytes());
+///     cue.extend_from_slice(b"data\x00\x00\x00\x00\x00\x00\x00\x00");
+///     cue.extend_from_slice(&100_u32.to_le_bytes());
+///     let adtl = b"LIST\x16\x00\x00\x00adtllabl\x0A\x00\x00\x00\x07\x00\x00\x00Intro\x00";
+///
+///     let mut bytes = [&plain[..], &cue, adtl].concat();
+///     let total_size = (bytes.len() as u32 - 8).to_le_bytes();
+///     bytes[4..8].copy_from_slice(&total_size);
+///
+///     let decoding = Format::decode_bytes(&bytes)?;
+///     let expected = CuePoint::new(7, 100, Some("Intro".to_string()));
+///     assert_eq!(decoding.cue_points, vec![expected]);
+///     assert!(decoding.trailing_chunks.is_empty());
+///     assert_eq!(decoding.to_bytes()?, bytes);
+///
+///     // Play order positions and chunk offsets are kept, as is the order of the chunks.
+///     cue[16..20].copy_from_slice(&3_u32.to_le_bytes());
+///     let info = b"LIST\x04\x00\x00\x00INFO";
+///     let mut bytes = [&plain[..], &cue, info, adtl].concat();
+///     let total_size = (bytes.len() as u32 - 8).to_le_bytes();
+///     bytes[4..8].copy_from_slice(&total_size);
+///
+///     let decoding = Format::decode_bytes(&bytes)?;
+///     assert_eq!(decoding.cue_points[0].position, 3);
+///     assert_eq!(decoding.trailing_chunks[0].data, b"INFO");
+///     assert_eq!(decoding.to_bytes()?, bytes);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CuePoint {
+    /// Identifier of the cue point, unique within the file.
+    pub id: u32,
+    /// Position of the cue point in the play order, the frame it marks without a playlist.
+    pub position: u32,
+    /// Tag of the chunk holding the marked audio data ("data").
+    pub data_chunk_id: [u8; 4],
+    /// Byte offset of that chunk within a wave list (0 for a `data` chunk).
+    pub chunk_start: u32,
+    /// Byte offset of the block holding the marked frame (0 for uncompressed audio data).
+    pub block_start: u32,
+    /// Frame of the audio data the cue point marks.
+    pub sample_offset: u32,
+    /// Text of the `labl` label keyed by `id`.
+    pub label: Option<String>,
+}
+
+impl CuePoint {
+    /// `new` returns a cue point marking a frame of the `data` chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Identifier of the cue point, unique within the file.
+    /// * `sample_offset` - Frame of the audio data the cue point marks.
+    /// * `label` - Text of the `labl` label.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::CuePoint;
+    ///
+    /// let cue_point = CuePoint::new(1, 12, None);
+    /// assert_eq!(cue_point.position, 12);
+    /// assert_eq!(cue_point.data_chunk_id, *b"data");
+    /// ```
+    #[must_use]
+    pub fn new(id: u32, sample_offset: u32, label: Option<String>) -> Self {
+        CuePoint {
+            id,
+            position: sample_offset,
+            data_chunk_id: *b"data",
+            chunk_start: 0,
+            block_start: 0,
+            sample_offset,
+            label,
+        }
+    }
+
+    // Parse every cue point of a `cue ` chunk body, without labels.
+    //
+    // Returns nothing unless the body holds exactly the cue points it counts, so that any other
+    // chunk is kept verbatim.
+    //
+    // # Arguments
+    //
+    // * `body` - Contents of the chunk without the tag and size.
+    fn parse(body: &[u8]) -> Vec<CuePoint> {
+        if body.len() < 4 || body.len() - 4 != le_u32(body, 0) as usize * 24 {
+            return Vec::new();
+        }
+
+        body[4..]
+            .chunks_exact(24)
+            .map(|record| CuePoint {
+                id: le_u32(record, 0),
+                position: le_u32(record, 4),
+                data_chunk_id: [record[8], record[9], record[10], record[11]],
+                chunk_start: le_u32(record, 12),
+                block_start: le_u32(record, 16),
+                sample_offset: le_u32(record, 20),
+                label: None,
+            })
+            .collect()
+    }
+
+    // Parse the `labl` labels of a `LIST` `adtl` chunk body into identifier and text pairs.
+    //
+    // Returns `None` unless the chunk holds only NUL-terminated UTF-8 `labl` labels, so that
+    // other associated data is kept verbatim.
+    //
+    // # Arguments
+    //
+    // * `body` - Contents of the chunk without the tag and size, starting with "adtl".
+    fn parse_labels(body: &[u8]) -> Option<Vec<(u32, String)>> {
+        let mut labels = Vec::new();
+        let mut rest = body.strip_prefix(b"adtl")?;
+        while !rest.is_empty() {
+            let size = le_u32(rest.get(..8)?, 4) as usize;
+            let text = rest.get(8..8 + size)?;
+            if rest[..4] != *b"labl" || size < 4 {
+                return None;
+            }
+
+            let id = le_u32(text, 0);
+            let text = text[4..].strip_suffix(&[0])?;
+            labels.push((id, String::from(core::str::from_utf8(text).ok()?)));
+            rest = rest.get(8 + size + size % 2..).unwrap_or_default();
+        }
+
+        Some(labels)
+    }
+
+    // Serialize cue points into a `cue ` chunk and, if any is labeled, a `LIST` `adtl` chunk.
+    //
+    // # Arguments
+    //
+    // * `cue_points` - Cue points to serialize.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn chunks(cue_points: &[CuePoint]) -> Result<Vec<Chunk>, anyhow::Error> {
+        let num_cue_points: u32 = cue_points.len().try_into()?;
+        let mut cue = num_cue_points.to_le_bytes().to_vec();
+        let mut adtl = b"adtl".to_vec();
+        for cue_point in cue_points {
+            for field in [cue_point.id, cue_point.position] {
+                cue.extend_from_slice(&field.to_le_bytes());
+            }
+            cue.extend_from_slice(&cue_point.data_chunk_id);
+            for field in [
+                cue_point.chunk_start,
+                cue_point.block_start,
+                cue_point.sample_offset,
+            ] {
+                cue.extend_from_slice(&field.to_le_bytes());
+            }
+
+            if let Some(label) = &cue_point.label {
+                let mut text = cue_point.id.to_le_bytes().to_vec();
+                text.extend_from_slice(label.as_bytes());
+                text.push(0);
+                Chunk {
+                    tag: *b"labl",
+                    data: text,
+                }
+                .write_to(&mut adtl)?;
+            }
+        }
+
+        let mut chunks = vec![Chunk {
+            tag: *b"cue ",
+            data: cue,
+        }];
+        if adtl.len() > 4 {
+            chunks.push(Chunk {
+                tag: *b"LIST",
+                data: adtl,
+            });
+        }
+        Ok(chunks)
+    }
+}
+
+/// Chunk-level fields of the sampler (`smpl`) chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sampler {
+    /// MIDI Manufacturers Association code of the sampler manufacturer (0 - none).
+    pub manufacturer: u32,
+    /// Product code of the sampler model (0 - none).
+    pub product: u32,
+    /// Duration of a sample in nanoseconds.
+    pub sample_period: u32,
+    /// MIDI note that plays the audio data at its original pitch.
+    pub midi_unity_note: u32,
+    /// Fraction of a semitone above `midi_unity_note` (`0x8000_0000` - half a semitone).
+    pub midi_pitch_fraction: u32,
+    /// SMPTE format (0 - none, 24, 25, 29 or 30 frames per second).
+    pub smpte_format: u32,
+    /// SMPTE time of the first sample as hours, minutes, seconds and frames.
+    pub smpte_offset: u32,
+    /// Manufacturer specific data following the loops.
+    pub sampler_data: Vec<u8>,
+}
+
+impl Sampler {
+    /// `new` returns sampler fields with no manufacturer, a unity note of middle C and the
+    /// sample period of `sampling_rate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_rate` - Sampling rate in the audio data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Sampler;
+    ///
+    /// let sampler = Sampler::new(16_000);
+    /// assert_eq!(sampler.sample_period, 62_500);
+    /// assert_eq!(sampler.midi_unity_note, 60);
+    /// assert!(sampler.sampler_data.is_empty());
+    /// ```
+    #[must_use]
+    pub fn new(sampling_rate: u32) -> Self {
+        Sampler {
+            manufacturer: 0,
+            product: 0,
+            sample_period: 1_000_000_000_u32.checked_div(sampling_rate).unwrap_or(0),
+            midi_unity_note: 60,
+            midi_pitch_fraction: 0,
+            smpte_format: 0,
+            smpte_offset: 0,
+            sampler_data: Vec::new(),
+        }
+    }
+
+    // Parse a `smpl` chunk body into the chunk-level fields and every loop.
+    //
+    // # Arguments
+    //
+    // * `body` - Contents of the chunk without the tag and size.
+    fn parse(body: &[u8]) -> Option<(Sampler, Vec<SampleLoop>)> {
+        if body.len() < 36 {
+            return None;
+        }
+
+        let records = body[36..].chunks_exact(24).take(le_u32(body, 28) as usize);
+        let sample_loops: Vec<SampleLoop> = records
+            .map(|record| SampleLoop {
+                cue_point_id: le_u32(record, 0),
+                loop_type: le_u32(record, 4),
+                start: le_u32(record, 8),
+                end: le_u32(record, 12),
+                fraction: le_u32(record, 16),
+                play_count: le_u32(record, 20),
+            })
+            .collect();
+
+        let sampler = Sampler {
+            manufacturer: le_u32(body, 0),
+            product: le_u32(body, 4),
+            sample_period: le_u32(body, 8),
+            midi_unity_note: le_u32(body, 12),
+            midi_pitch_fraction: le_u32(body, 16),
+            smpte_format: le_u32(body, 20),
+            smpte_offset: le_u32(body, 24),
+            sampler_data: body[36 + sample_loops.len() * 24..].to_vec(),
+        };
+        Some((sampler, sample_loops))
+    }
+
+    // Serialize the sampler fields and loops into a `smpl` chunk.
+    //
+    // # Arguments
+    //
+    // * `loops` - Loops to serialize.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn chunk(&self, loops: &[SampleLoop]) -> Result<Chunk, anyhow::Error> {
+        let num_loops: u32 = loops.len().try_into()?;
+        let sampler_data_size: u32 = self.sampler_data.len().try_into()?;
+
+        let mut fields = vec![
+            self.manufacturer,
+            self.product,
+            self.sample_period,
+            self.midi_unity_note,
+            self.midi_pitch_fraction,
+            self.smpte_format,
+            self.smpte_offset,
+            num_loops,
+            sampler_data_size,
+        ];
+        for sample_loop in loops {
+            fields.extend([
+                sample_loop.cue_point_id,
+                sample_loop.loop_type,
+                sample_loop.start,
+                sample_loop.end,
+                sample_loop.fraction,
+                sample_loop.play_count,
+            ]);
+        }
+
+        let mut data: Vec<u8> = fields
+            .iter()
+            .flat_map(|field| field.to_le_bytes())
+            .collect();
+        data.extend_from_slice(&self.sampler_data);
+        Ok(Chunk {
+            tag: *b"smpl",
+            data,
+        })
+    }
+}
+
+/// Loop points from the sampler (`smpl`) chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SampleLoop {
+    /// Identifier of the cue point naming the loop (0 - none).
+    pub cue_point_id: u32,
+    /// Loop type (0 - forward, 1 - alternating, 2 - backward).
+    pub loop_type: u32,
+    /// First frame of the loop.
+    pub start: u32,
+    /// Last frame of the loop.
+    pub end: u32,
+    /// Fraction of a frame at which the loop ends (`0x8000_0000` - half a frame).
+    pub fraction: u32,
+    /// Number of times the loop plays (0 - infinitely).
+    pub play_count: u32,
+}
+
+impl SampleLoop {
+    /// `new` returns a forward loop played infinitely from frame `start` through `end`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - First frame of the loop.
+    /// * `end` - Last frame of the loop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::SampleLoop;
+    ///
+    /// let sample_loop = SampleLoop::new(4, 27);
+    /// assert_eq!((sample_loop.loop_type, sample_loop.play_count), (0, 0));
+    /// assert_eq!((sample_loop.start, sample_loop.end), (4, 27));
+    /// ```
+    #[must_use]
+    pub fn new(start: u32, end: u32) -> Self {
+        SampleLoop {
+            cue_point_id: 0,
+            loop_type: 0,
+            start,
+            end,
+            fraction: 0,
+            play_count: 0,
+        }
+    }
+}
+
+/// Channel layout of the audio data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channels {
+    /// A single channel.
+    Mono,
+    /// Two channels (left and right).
+    Stereo,
+    /// Any other number of channels.
+    Multi(u16),
+}
+
+impl From<u16> for Channels {
+    fn from(num_channels: u16) -> Self {
+        match num_channels {
+            1 => Channels::Mono,
+            2 => Channels::Stereo,
+            _ => Channels::Multi(num_channels),
+        }
+    }
+}
+
+impl From<Channels> for u16 {
+    fn from(channels: Channels) -> Self {
+        match channels {
+            Channels::Mono => 1,
+            Channels::Stereo => 2,
+            Channels::Multi(num_channels) => num_channels,
+        }
+    }
+}
+
+/// G.711 companding of 8-bit audio data, stored with format codes 6 and 7.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Companding {
+    /// A-law (format code 6).
+    ALaw,
+    /// Mu-law (format code 7).
+    MuLaw,
+}
+
+/// Conversion used by [`Format::resample_poly`] for a pair of sampling rates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResamplePath {
+    /// Lowpass filter and keep every n-th frame.
+    Decimate(u32),
+    /// Insert n - 1 linearly interpolated frames after every frame.
+    Interpolate(u32),
+    /// General linear interpolation, see [`Format::resample`].
+    General,
+}
+
+/// Quality of a [`Resampler`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResampleQuality {
+    /// Linear interpolation, see [`Format::resample`].
+    Linear,
+    /// Lowpass filtering before linear interpolation when the rate decreases, see
+    /// [`Format::resample_anti_aliased`].
+    AntiAliased,
+}
+
+/// Converter between a pair of sampling rates, reusable across files.
+///
+/// Filter coefficients are computed once by [`Resampler::new`], so converting many files to the
+/// same rate does not repeat that work.
+///
+/// # Example
+///
+/// ```
+/// use wavepcm::{Format, ResampleQuality, Resampler};
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let resampler = Resampler::new(48_000, 16_000, ResampleQuality::AntiAliased)?;
+///     for seed in [1, 2] {
+///         let noise = Format::white_noise(std::time::Duration::from_millis(50), 48_000, 0.5, seed)?;
+///         let (expected, anti_aliased) = noise.resample_anti_aliased(16_000)?;
+///         assert!(anti_aliased);
+///         assert!(resampler.process(&noise)? == expected);
+///     }
+///
+///     let linear = Resampler::new(48_000, 44_100, ResampleQuality::Linear)?;
+///     let noise = Format::white_noise(std::time::Duration::from_millis(50), 48_000, 0.5, 3)?;
+///     assert!(linear.process(&noise)? == noise.resample(44_100)?);
+///
+///     let other = Format::encode(vec![0u8; 16], 1, 44_100, 16)?;
+///     assert!(resampler.process(&other).is_err());
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resampler {
+    source_rate: u32,
+    sampling_rate: u32,
+    lowpass: Option<BiquadCoeffs>,
+}
+
+#[cfg(feature = "std")]
+impl Resampler {
+    /// `new` prepares a conversion from `source_rate` to `sampling_rate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_rate` - Sampling rate of the files to process.
+    /// * `sampling_rate` - Sampling rate of the resampled audio data.
+    /// * `quality` - Resampling quality.
+    ///
+    /// # Errors
+    ///
+    /// If either sampling rate is 0.
+    pub fn new(
+        source_rate: u32,
+        sampling_rate: u32,
+        quality: ResampleQuality,
+    ) -> Result<Self, anyhow::Error> {
+        if source_rate == 0 || sampling_rate == 0 {
+            return Err(anyhow::anyhow!(
+                "Resampling requires non-zero sampling rates, got {source_rate} and {sampling_rate} instead."
+            ));
+        }
+
+        let lowpass = (quality == ResampleQuality::AntiAliased && sampling_rate < source_rate)
+            .then(|| BiquadCoeffs::anti_alias(source_rate, sampling_rate));
+        Ok(Resampler {
+            source_rate,
+            sampling_rate,
+            lowpass,
+        })
+    }
+
+    /// `process` converts a file to the sampling rate of the resampler.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - File at the source rate of the resampler.
+    ///
+    /// # Errors
+    ///
+    /// If the file has another sampling rate, or the audio data is neither 8, 16, 24 or 32-bit
+    /// integer PCM nor 32-bit float PCM.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn process(&self, format: &Format) -> Result<Format, anyhow::Error> {
+        let source_rate = u32::from_le_bytes(format.sampling_rate);
+        if source_rate != self.source_rate {
+            return Err(anyhow::anyhow!(
+                "Resampler expects a sampling rate of {}, got {source_rate} instead.",
+                self.source_rate
+            ));
+        }
+
+        let num_channels = usize::from(u16::from_le_bytes(format.num_channels).max(1));
+        let mut samples = format.normalized_f32()?;
+        if let Some(lowpass) = &self.lowpass {
+            samples = anti_alias(&samples, num_channels, lowpass)
+                .into_iter()
+                .map(|sample| sample as f32)
+                .collect();
+        }
+
+        let output = interpolate_linear(&samples, num_channels, source_rate, self.sampling_rate)?;
+        format.with_resampled_f32(&output, self.sampling_rate)
+    }
+}
+
+/// De-interleaved 16-bit channels of a file, written back into it when dropped.
+///
+/// Returned by [`Format::channels_mut_i16`]. The guard dereferences to one buffer per channel,
+/// edits are interleaved back into the audio data by [`ChannelsGuard::commit`] or when the guard
+/// goes out of scope. Samples past the original length of a buffer are ignored.
+#[must_use]
+pub struct ChannelsGuard<'a> {
+    format: &'a mut Format,
+    channels: Vec<Vec<i16>>,
+}
+
+impl ChannelsGuard<'_> {
+    /// `commit` writes the channels back into the file and releases it.
+    ///
+    /// This is what dropping the guard does, spelled out at the call site.
+    pub fn commit(self) {
+        drop(self);
+    }
+}
+
+impl core::ops::Deref for ChannelsGuard<'_> {
+    type Target = [Vec<i16>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.channels
+    }
+}
+
+impl core::ops::DerefMut for ChannelsGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.channels
+    }
+}
+
+impl Drop for ChannelsGuard<'_> {
+    fn drop(&mut self) {
+        let frame_size = 2 * usize::from(u16::from_le_bytes(self.format.num_channels).max(1));
+        for (channel, samples) in self.channels.iter().enumerate() {
+            let frames = self.format.data.chunks_exact_mut(frame_size);
+            for (frame, sample) in frames.zip(samples) {
+                frame[2 * channel..2 * channel + 2].copy_from_slice(&sample.to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Sample format of the audio data, combining the format code and the bit depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SampleFormat {
+    /// Unsigned 8-bit integer PCM.
+    U8,
+    /// Signed 16-bit integer PCM.
+    I16,
+    /// Signed 24-bit integer PCM.
+    I24,
+    /// Signed 32-bit integer PCM.
+    I32,
+    /// 32-bit IEEE float PCM.
+    F32,
+    /// 64-bit IEEE float PCM.
+    F64,
+}
+
+impl SampleFormat {
+    // Return the number of bits per sample.
+    fn bits_per_sample(self) -> u16 {
+        match self {
+            SampleFormat::U8 => 8,
+            SampleFormat::I16 => 16,
+            SampleFormat::I24 => 24,
+            SampleFormat::I32 | SampleFormat::F32 => 32,
+            SampleFormat::F64 => 64,
+        }
+    }
+
+    // Return the format code (1 for integer PCM, 3 for float PCM).
+    fn fmt_code(self) -> u16 {
+        match self {
+            SampleFormat::F32 | SampleFormat::F64 => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// Difference between two files, see [`Format::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldDiff {
+    /// A header field or metadata chunk differs.
+    Field {
+        /// Name of the field (e.g. `"sampling_rate"`).
+        name: &'static str,
+        /// Value in the first file.
+        left: String,
+        /// Value in the second file.
+        right: String,
+    },
+    /// The audio data differs.
+    Data {
+        /// Offset of the first differing byte, or the length of the shorter data when one is a
+        /// prefix of the other.
+        first_byte: usize,
+    },
+}
+
+/// Stream configuration for audio output libraries such as `cpal` or `rodio`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerParams {
+    /// Sampling rate in frames per second.
+    pub sampling_rate: u32,
+    /// Number of interleaved channels.
+    pub num_channels: u16,
+    /// Sample format of the interleaved samples.
+    pub sample_format: SampleFormat,
+}
+
+/// Features compiled into the installed crate, see [`capabilities`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Capabilities {
+    /// Version of the crate.
+    pub version: &'static str,
+    /// Whether the `std` feature is enabled, providing file and stream I/O and signal processing.
+    pub std: bool,
+    /// Whether `serde` serialization is supported.
+    pub serde: bool,
+    /// Whether RF64 files larger than 4 GiB are supported.
+    pub rf64: bool,
+    /// Whether 32-bit float PCM samples can be converted to and from integer PCM.
+    pub float: bool,
+    /// Whether A-law and mu-law audio data can be decoded.
+    pub companding: bool,
+    /// Whether the `ndarray` feature is enabled, providing arrays of samples.
+    pub ndarray: bool,
+    /// Bits per sample of the supported integer PCM formats.
+    pub bit_depths: &'static [u16],
+}
+
+/// `capabilities` reports the version and features of the installed crate.
+///
+/// Without the `std` feature only the baseline of integer PCM encoding and decoding is
+/// available, so `std`, `float` and `companding` are `false`.
+///
+/// # Example
+///
+/// ```
+/// fn main() -> Result<(), anyhow::Error> {
+///     let capabilities = wavepcm::capabilities();
+///     assert_eq!(capabilities.version, env!("CARGO_PKG_VERSION"));
+///     assert!(capabilities.std && capabilities.float && capabilities.companding);
+///     assert!(!capabilities.serde && !capabilities.rf64);
+///     assert_eq!(capabilities.bit_depths, [8, 16, 24, 32]);
+///     Ok(())
+/// }
+/// ```
+#[must_use]
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        std: cfg!(feature = "std"),
+        serde: false,
+        rf64: false,
+        float: cfg!(feature = "std"),
+        companding: cfg!(feature = "std"),
+        ndarray: cfg!(feature = "ndarray"),
+        bit_depths: &[8, 16, 24, 32],
+    }
+}
+
+// Bytes 2 - 16 of the sub-format GUID of the extensible format, the first two hold the format
+// code.
+const SUBFORMAT_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// Sampling rates in common use, in ascending order.
+pub const STANDARD_SAMPLING_RATES: [u32; 9] = [
+    8_000, 11_025, 16_000, 22_050, 44_100, 48_000, 88_200, 96_000, 192_000,
+];
+
+/// Largest number of channels accepted by [`Format::encode`] and [`Format::check`].
+///
+/// Corrupt headers can claim tens of thousands of channels, which would otherwise lead to huge
+/// allocations when de-interleaving.
+pub const MAX_NUM_CHANNELS: u16 = 256;
+
+/// WAVE PCM file format.
+///
+/// Formats compare and hash by every field, including the audio data, so they can be
+/// deduplicated in a `HashSet`. See [`Format::data_hash`] for a cheaper fingerprint.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use wavepcm::Format;
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let mut formats = HashSet::new();
+///     formats.insert(Format::encode(vec![1u8; 16], 1, 16_000, 16)?);
+///     formats.insert(Format::encode(vec![1u8; 16], 1, 16_000, 16)?);
+///     formats.insert(Format::encode(vec![2u8; 16], 1, 16_000, 16)?);
+///     formats.insert(Format::encode(vec![1u8; 16], 2, 16_000, 16)?);
+///     assert_eq!(formats.len(), 3);
+///     Ok(())
+/// }
+/// ```
+///
+/// Methods that transform audio data return a new `Format`, which must be used:
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// use wavepcm::Format;
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+///     encoding.resample(8_000)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct Format {
+    /// RIFF tag ("RIFF").
+    pub riff_tag: [u8; 4],
+    /// Total size of a file in bytes.
+    pub total_size: [u8; 4],
+    /// WAVE tag ("WAVE").
+    pub wave_tag: [u8; 4],
+    /// Format tag ("fmt ").
+    pub fmt_chunk_tag: [u8; 4],
+    /// Format chunk size (16 for PCM).
+    pub fmt_chunk_size: [u8; 4],
+    /// Format type (1 for PCM - uncompressed).
+    pub fmt_code: [u8; 2],
+    /// Number of channels in the audio data.
+    pub num_channels: [u8; 2],
+    /// Sampling rate in the audio data (blocks per second).
+    pub sampling_rate: [u8; 4],
+    /// Byte rate (`sampling_rate * num_channels * bits_per_sample / 8`).
+    pub byte_rate: [u8; 4],
+    /// Block alignment value (`num_channels * bits_per_sample / 8`).
+    pub block_alignment: [u8; 2],
+    /// Bits per sample in the audio data (8 - 8 bits unsigned, 16 - 16 bits signed, etc).
+    pub bits_per_sample: [u8; 2],
+    /// Format chunk bytes following `bits_per_sample` (empty for PCM, `cbSize` and the
+    /// extension for other formats).
+    pub fmt_extension: Vec<u8>,
+    /// Data tag ("data").
+    pub data_tag: [u8; 4],
+    /// Size of the audio data (`num_samples * num_channels * bits_per_sample / 8`).
+    pub data_size: [u8; 4],
+    /// Raw audio data.
+    pub data: Vec<u8>,
+    /// Loop points from the sampler (`smpl`) chunk, written after the audio data.
+    pub sample_loops: Vec<SampleLoop>,
+    /// Chunk-level fields of the sampler (`smpl`) chunk, `None` if the file has none.
+    pub sampler: Option<Sampler>,
+    /// Broadcast Wave Format metadata from the `bext` chunk, written before the audio data.
+    pub broadcast_info: Option<BroadcastInfo>,
+    /// Playback parameters from the instrument (`inst`) chunk, written after the audio data.
+    pub instrument: Option<Instrument>,
+    /// Cue points from the `cue ` chunk, labeled from the `LIST` `adtl` chunk, written after the
+    /// audio data.
+    pub cue_points: Vec<CuePoint>,
+    /// Unknown chunks found before the audio data, in file order.
+    pub leading_chunks: Vec<Chunk>,
+    /// Unknown chunks found after the audio data, in file order.
+    pub trailing_chunks: Vec<Chunk>,
+    /// Kinds of the chunks in file order, empty if they are in the order written by default.
+    chunk_order: Vec<ChunkKind>,
+    /// Length of the file the format was decoded from, kept only if `total_size` disagrees.
+    file_size: Option<u64>,
+}
+
+impl Format {
+    /// `encode` encodes WAVE PCM file.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Raw audio data.
+    /// * `num_channels` - Number of channels in the audio data.
+    /// * `sampling_rate` - Sampling rate in the audio data.
+    /// * `bits_per_sample` - Bits per sample in the audio data.
+    ///
+    /// # Errors
+    ///
+    /// If `num_channels` exceeds [`MAX_NUM_CHANNELS`] or the value cannot fit when performing
+    /// type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// let data = vec![1u8; 16];
+    /// let num_channels = 1;
+    /// let sampling_rate = 16_000;
+    /// let bits_per_sample = 16;
+    /// let encoding = Format::encode(data, num_channels, sampling_rate, bits_per_sample);
+    /// ```
+    pub fn encode(
+        data: Vec<u8>,
+        num_channels: u16,
+        sampling_rate: u32,
+        bits_per_sample: u16,
+    ) -> Result<Self, anyhow::Error> {
+        if num_channels > MAX_NUM_CHANNELS {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format supports at most {MAX_NUM_CHANNELS} channels, got {num_channels} instead."
+            ));
+        }
+
+        let size: u32 = data.len().try_into()?;
+
+        let riff_tag = "RIFF".as_bytes().try_into()?;
+        let total_size = (size + 36).to_le_bytes();
+        let wave_tag = "WAVE".as_bytes().try_into()?;
+        let fmt_chunk_tag = "fmt ".as_bytes().try_into()?;
+        let fmt_chunk_size = 16_u32.to_le_bytes();
+        let fmt_code = 1_u16.to_le_bytes();
+        let byte_rate = (sampling_rate * u32::from(num_channels) * u32::from(bits_per_sample) / 8)
+            .to_le_bytes();
+        let block_alignment = (num_channels * bits_per_sample / 8).to_le_bytes();
+        let data_tag = "data".as_bytes().try_into()?;
+        let data_size = size.to_le_bytes();
+
+        Ok(Format {
+            riff_tag,
+            total_size,
+            wave_tag,
+            fmt_chunk_tag,
+            fmt_chunk_size,
+            fmt_code,
+            num_channels: num_channels.to_le_bytes(),
+            sampling_rate: sampling_rate.to_le_bytes(),
+            byte_rate,
+            block_alignment,
+            bits_per_sample: bits_per_sample.to_le_bytes(),
+            fmt_extension: Vec::new(),
+            data_tag,
+            data_size,
+            data,
+            sample_loops: Vec::new(),
+            sampler: None,
+            broadcast_info: None,
+            instrument: None,
+            cue_points: Vec::new(),
+            leading_chunks: Vec::new(),
+            trailing_chunks: Vec::new(),
+            chunk_order: Vec::new(),
+            file_size: None,
+        })
+    }
+
+    /// `encode_sample_format` encodes WAVE file, deriving the format chunk from the sample
+    /// format.
+    ///
+    /// Integer audio data of up to 16 bits and 2 channels gets the plain 16-byte format chunk.
+    /// Float audio data of up to 2 channels gets an 18-byte format chunk with `cbSize = 0`.
+    /// Anything else, i.e. more than 2 channels or integer samples wider than 16 bits, uses the
+    /// 40-byte extensible format chunk with the matching sub-format, as Windows expects.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Raw audio data.
+    /// * `num_channels` - Number of channels in the audio data.
+    /// * `sampling_rate` - Sampling rate in the audio data.
+    /// * `sample_format` - Sample format of the audio data.
+    ///
+    /// # Errors
+    ///
+    /// If `num_channels` exceeds [`MAX_NUM_CHANNELS`] or the value cannot fit when performing
+    /// type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode_sample_format(vec![0u8; 16], 2, 48_000, SampleFormat::F32)?;
+    ///     assert_eq!(u32::from_le_bytes(encoding.fmt_chunk_size), 18);
+    ///     assert_eq!(u16::from_le_bytes(encoding.fmt_code), 3);
+    ///     assert_eq!(encoding.fmt_extension, vec![0, 0]);
+    ///     encoding.check()?;
+    ///
+    ///     let encoding = Format::encode_sample_format(vec![0u8; 12], 2, 48_000, SampleFormat::I24)?;
+    ///     assert_eq!(u32::from_le_bytes(encoding.fmt_chunk_size), 40);
+    ///     encoding.assert_pcm()?;
+    ///     encoding.check()?;
+    ///
+    ///     let bytes = encoding.to_bytes()?;
+    ///     assert_eq!(bytes.len(), 12 + 48 + 8 + 12);
+    ///     assert_eq!(Format::decode_bytes(&bytes)?.fmt_extension, encoding.fmt_extension);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_sample_format(
+        data: Vec<u8>,
+        num_channels: u16,
+        sampling_rate: u32,
+        sample_format: SampleFormat,
+    ) -> Result<Self, anyhow::Error> {
+        let bits_per_sample = sample_format.bits_per_sample();
+        let fmt_code = sample_format.fmt_code();
+        let mut format = Format::encode(data, num_channels, sampling_rate, bits_per_sample)?;
+
+        if num_channels > 2 || (fmt_code == 1 && bits_per_sample > 16) {
+            let mut extension = Vec::with_capacity(24);
+            extension.extend_from_slice(&22_u16.to_le_bytes());
+            extension.extend_from_slice(&bits_per_sample.to_le_bytes());
+            extension.extend_from_slice(&0_u32.to_le_bytes());
+            extension.extend_from_slice(&fmt_code.to_le_bytes());
+            extension.extend_from_slice(&SUBFORMAT_GUID_TAIL);
+            format.fmt_code = 0xFFFE_u16.to_le_bytes();
+            format.fmt_extension = extension;
+        } else if fmt_code == 3 {
+            format.fmt_code = fmt_code.to_le_bytes();
+            format.fmt_extension = 0_u16.to_le_bytes().to_vec();
+        }
+
+        format.fmt_chunk_size = (16 + u32::try_from(format.fmt_extension.len())?).to_le_bytes();
+        format.sync_total_size()?;
+        Ok(format)
+    }
+
+    /// `encode_planar_i16` encodes WAVE PCM file from separate 16-bit channel buffers.
+    ///
+    /// The channels are interleaved with [`interleave_i16`], which rejects ragged lengths.
+    ///
+    /// # Arguments
+    ///
+    /// * `channels` - Samples of every channel.
+    /// * `sampling_rate` - Sampling rate in the audio data.
+    ///
+    /// # Errors
+    ///
+    /// If there are no channels, the channels differ in length, there are more than
+    /// [`MAX_NUM_CHANNELS`] channels or the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let left = vec![1_i16; 1_000];
+    ///     let right = vec![-1_i16; 1_000];
+    ///     let encoding = Format::encode_planar_i16(&[left, right], 16_000)?;
+    ///     encoding.check()?;
+    ///     assert_eq!(u16::from_le_bytes(encoding.num_channels), 2);
+    ///     assert_eq!(encoding.frame_count(), 1_000);
+    ///
+    ///     assert!(Format::encode_planar_i16(&[vec![0; 3], vec![0; 2]], 16_000).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_planar_i16(
+        channels: &[Vec<i16>],
+        sampling_rate: u32,
+    ) -> Result<Self, anyhow::Error> {
+        let channels: Vec<&[i16]> = channels.iter().map(Vec::as_slice).collect();
+        let data = interleave_i16(&channels)?;
+        Format::encode(data, u16::try_from(channels.len())?, sampling_rate, 16)
+    }
+
+    /// `from_raw_pcm` encodes headerless PCM read from a reader.
+    ///
+    /// Every byte up to the end of the reader becomes the audio data, and the header fields are
+    /// computed as in [`Format::encode`].
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader of raw audio data.
+    /// * `num_channels` - Number of channels in the audio data.
+    /// * `sampling_rate` - Sampling rate in the audio data.
+    /// * `bits_per_sample` - Bits per sample in the audio data.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, `num_channels` exceeds [`MAX_NUM_CHANNELS`] or the value cannot fit
+    /// when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let raw = vec![1u8; 32];
+    ///     let encoding = Format::from_raw_pcm(&raw[..], 2, 16_000, 16)?;
+    ///     encoding.check()?;
+    ///     assert_eq!(encoding.data, raw);
+    ///     assert_eq!(encoding.frame_count(), 8);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_raw_pcm<R: Read>(
+        mut reader: R,
+        num_channels: u16,
+        sampling_rate: u32,
+        bits_per_sample: u16,
+    ) -> Result<Self, anyhow::Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Format::encode(data, num_channels, sampling_rate, bits_per_sample)
+    }
+
+    /// `decode` decodes WAVE PCM file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the WAV PCM file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` does not already exist.
+    /// Other errors may also be returned according to `OpenOptions::open`.
+    ///
+    /// A `total_size` that does not match the length of the file is not an error, as trailing
+    /// bytes and the `0xFFFFFFFF` placeholder left by streaming writers are common in practice.
+    /// The mismatch is reported by [`Format::total_size_matches`] and
+    /// [`Format::consistency_report`], and rejected by [`Format::decode_strict`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let decoding = Format::decode("sample.wav")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// A file that is longer than its `total_size` claims is decoded and flagged:
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_decode_total_size.wav");
+    ///     let path = path.to_str().unwrap();
+    ///
+    ///     let encoding = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     encoding.write(path)?;
+    ///     assert!(Format::decode(path)?.total_size_matches());
+    ///
+    ///     let mut bytes = encoding.to_bytes()?;
+    ///     bytes.push(0);
+    ///     std::fs::write(path, bytes)?;
+    ///
+    ///     let decoding = Format::decode(path)?;
+    ///     assert_eq!(decoding.data, encoding.data);
+    ///     assert!(!decoding.total_size_matches());
+    ///     assert!(!decoding.consistency_report().is_empty());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode(path: &str) -> Result<Self, anyhow::Error> {
+        Format::decode_with_progress(path, |_, _| {})
+    }
+
+    /// `decode_with_progress` decodes WAVE PCM file, reporting progress while reading.
+    ///
+    /// The callback receives the number of bytes read so far and the file size. It is called
+    /// once for every 64 KiB read and a final time once the whole file has been read, so the
+    /// last call always reports completion.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the WAV PCM file.
+    /// * `on_progress` - Callback receiving `(bytes_read, total_bytes)`.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be decoded, see [`Format::decode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_decode_with_progress.wav");
+    ///     Format::encode(vec![0u8; 200_000], 1, 16_000, 16)?.write(path.to_str().unwrap())?;
+    ///
+    ///     let mut reports = Vec::new();
+    ///     let decoding = Format::decode_with_progress(&path, |bytes_read, total_bytes| {
+    ///         reports.push((bytes_read, total_bytes));
+    ///     })?;
+    ///
+    ///     assert_eq!(decoding.data.len(), 200_000);
+    ///     assert!(reports.len() > 1);
+    ///     assert!(reports.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    ///     assert_eq!(reports.last(), Some(&(200_044, 200_044)));
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode_with_progress<P, F>(path: P, on_progress: F) -> Result<Self, anyhow::Error>
+    where
+        P: AsRef<Path>,
+        F: FnMut(u64, u64),
+    {
+        let file = File::open(path)?;
+        let file_size = file.metadata()?.len();
+        let mut source = ProgressSource {
+            source: IoSource(BufReader::new(file)),
+            bytes_read: 0,
+            total_bytes: file_size,
+            next_report: PROGRESS_BLOCK as u64,
+            on_progress,
+        };
+        let mut format = Format::decode_from(&mut source)?;
+        (source.on_progress)(source.bytes_read, file_size);
+
+        if u64::from(u32::from_le_bytes(format.total_size)) + 8 != file_size {
+            format.file_size = Some(file_size);
+        }
+
+        Ok(format)
+    }
+
+    /// `decode_strict` decodes WAVE PCM file, rejecting it if `total_size` does not match the
+    /// length of the file.
+    ///
+    /// [`Format::decode`] accepts such files, as trailing bytes and the `0xFFFFFFFF` placeholder
+    /// left by streaming writers are common in practice, and only reports the mismatch through
+    /// [`Format::total_size_matches`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the WAV PCM file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be decoded, see [`Format::decode`], or `total_size` does not match the
+    /// length of the file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_decode_strict.wav");
+    ///     let path = path.to_str().unwrap();
+    ///
+    ///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     encoding.write(path)?;
+    ///     assert!(Format::decode_strict(path)? == encoding);
+    ///
+    ///     // One trailing byte past the RIFF chunk.
+    ///     let mut bytes = encoding.to_bytes()?;
+    ///     bytes.push(0);
+    ///     std::fs::write(path, &bytes)?;
+    ///     assert_eq!(Format::decode(path)?.data, encoding.data);
+    ///     assert!(Format::decode_strict(path).is_err());
+    ///
+    ///     // Placeholder total size of a streaming writer.
+    ///     bytes.pop();
+    ///     bytes[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+    ///     std::fs::write(path, &bytes)?;
+    ///     assert_eq!(Format::decode(path)?.data, encoding.data);
+    ///     assert!(Format::decode_strict(path).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode_strict<P: AsRef<Path>>(path: P) -> Result<Self, anyhow::Error> {
+        let format = Format::decode_with_progress(path, |_, _| {})?;
+
+        if let Some(file_size) = format.file_size {
+            let expected = u64::from(u32::from_le_bytes(format.total_size)) + 8;
+            return Err(anyhow::anyhow!(
+                "WAVE PCM file is {file_size} bytes long, but its total size of bytes 5 - 8 implies {expected} bytes."
+            ));
+        }
+
+        Ok(format)
+    }
+
+    /// `decode_mmap` decodes WAVE PCM file, memory-mapping the audio data instead of reading it.
+    ///
+    /// Requires the `mmap` feature. Only the header is read up front, pages of audio data are
+    /// loaded by the operating system as they are accessed, which suits files read once or
+    /// larger than memory. The file must not be modified while it is mapped. A data chunk
+    /// running past the end of the file is cut short.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the WAV PCM file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be opened or mapped, it has no format chunk or data chunk, or the
+    /// value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_decode_mmap.wav");
+    ///     let data = interleave_i16(&[&[1, 2, 3], &[-4, -5, -6]])?;
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///     encoding.write(path.to_str().unwrap())?;
+    ///
+    ///     let mapped = Format::decode_mmap(&path)?;
+    ///     assert_eq!(mapped.data(), &encoding.data[..]);
+    ///     assert_eq!(mapped.frame_count(), 3);
+    ///     assert_eq!(mapped.sample_at_i16(1, 1)?, -5);
+    ///     assert!(mapped.sample_at_i16(3, 0).is_err());
+    ///
+    ///     let mut channels = vec![Vec::new(), Vec::new()];
+    ///     mapped.deinterleave_into_i16(&mut channels)?;
+    ///     assert_eq!(channels, vec![vec![1, 2, 3], vec![-4, -5, -6]]);
+    ///     assert!(mapped.to_format()? == encoding);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn decode_mmap<P: AsRef<Path>>(path: P) -> Result<MmapFormat, anyhow::Error> {
+        let file = File::open(path)?;
+        // SAFETY: The map is read-only, modifying the file while it is mapped is ruled out by
+        // the documented contract of this function.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+
+        let decoder = Decoder::new(&map[..])?;
+        let header = decoder.header()?;
+        // The decoder reads up to the audio data, leaving the rest of the map unread.
+        let start = map.len() - decoder.reader.len();
+        let end = start
+            .saturating_add(usize::try_from(decoder.data_size)?)
+            .min(map.len());
+
+        Ok(MmapFormat {
+            header,
+            map,
+            data: start..end,
+        })
+    }
+
+    /// `decode_tolerant` decodes WAVE PCM file whose standard tags are miscased or mispadded.
+    ///
+    /// Some broken encoders write tags such as `Data` or `fmt\0`. Before decoding, the `RIFF`,
+    /// `WAVE`, `fmt ` and `data` tags are compared case-insensitively with padding spaces and
+    /// NUL bytes trimmed, and repaired to their canonical form. A description of every repair is
+    /// returned alongside the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the WAV PCM file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read or its header is truncated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_decode_tolerant.wav");
+    ///     let path = path.to_str().unwrap();
+    ///
+    ///     let mut bytes = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+    ///     bytes[36..40].copy_from_slice(b"Data");
+    ///     std::fs::write(path, bytes)?;
+    ///
+    ///     assert!(Format::decode(path)?.check().is_err());
+    ///
+    ///     let (decoding, repairs) = Format::decode_tolerant(path)?;
+    ///     decoding.check()?;
+    ///     assert_eq!(decoding.data, vec![1u8; 16]);
+    ///     assert_eq!(repairs, vec!["Repaired tag \"Data\" at offset 36 to \"data\"."]);
+    ///
+    ///     // A mispadded format tag following a LIST chunk.
+    ///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+    ///     let mut bytes = encoding[..12].to_vec();
+    ///     bytes.extend_from_slice(b"LIST\x04\x00\x00\x00INFOfmt\0");
+    ///     bytes.extend_from_slice(&encoding[16..]);
+    ///     let total_size = (bytes.len() as u32 - 8).to_le_bytes();
+    ///     bytes[4..8].copy_from_slice(&total_size);
+    ///     std::fs::write(path, bytes)?;
+    ///
+    ///     let (decoding, repairs) = Format::decode_tolerant(path)?;
+    ///     decoding.check()?;
+    ///     assert_eq!(decoding.data, vec![1u8; 16]);
+    ///     assert_eq!(repairs, vec!["Repaired tag \"fmt\\x00\" at offset 24 to \"fmt \"."]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode_tolerant<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<String>), anyhow::Error> {
+        let mut bytes = std::fs::read(path)?;
+        let mut repairs = Vec::new();
+
+        let mut repair = |bytes: &mut [u8], offset: usize, canonical: &[u8; 4]| {
+            let Some(tag) = bytes.get_mut(offset..offset + 4) else {
+                return;
+            };
+            let trimmed: Vec<u8> = tag
+                .iter()
+                .copied()
+                .filter(|byte| !matches!(byte, b' ' | 0))
+                .collect();
+            if tag == canonical || !trimmed.eq_ignore_ascii_case(canonical.trim_ascii_end()) {
+                return;
+            }
+
+            let found = tag.escape_ascii().to_string();
+            let expected = String::from_utf8_lossy(canonical);
+            repairs.push(format!(
+                "Repaired tag \"{found}\" at offset {offset} to \"{expected}\"."
+            ));
+            tag.copy_from_slice(canonical);
+        };
+
+        // The format and data chunks may follow any other chunk, so every chunk tag is a
+        // candidate for either repair.
+        repair(&mut bytes, 0, b"RIFF");
+        repair(&mut bytes, 8, b"WAVE");
+        let mut offset = 12;
+        while offset <= bytes.len().saturating_sub(8) {
+            repair(&mut bytes, offset, b"fmt ");
+            repair(&mut bytes, offset, b"data");
+            let size = usize::try_from(le_u32(&bytes, offset + 4))?;
+            // A size running past the end of the address space cannot be followed by a chunk.
+            match size
+                .checked_add(8 + size % 2)
+                .and_then(|len| offset.checked_add(len))
+            {
+                Some(next) => offset = next,
+                None => break,
+            }
+        }
+
+        let format = Format::decode_bytes(&bytes)?;
+        Ok((format, repairs))
+    }
+
+    /// `decode_lossy` decodes WAVE PCM file, dropping a trailing partial frame.
+    ///
+    /// Recorders that are cut off mid-frame leave audio data that is not a multiple of the block
+    /// alignment, which [`Format::check`] rejects, and sizes describing the data they meant to
+    /// write. This function truncates the dangling bytes, clamps `data_size` to the audio data
+    /// actually present and updates `total_size`. The number of dropped bytes is returned
+    /// alongside the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the WAV PCM file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be decoded, see [`Format::decode`], or the value cannot fit when
+    /// performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_decode_lossy.wav");
+    ///     let path = path.to_str().unwrap();
+    ///
+    ///     // A recording of four 16-bit mono samples cut off in the middle of the third, leaving
+    ///     // the sizes written up front stale.
+    ///     let mut bytes = Format::encode(vec![1u8; 8], 1, 16_000, 16)?.to_bytes()?;
+    ///     bytes.truncate(bytes.len() - 3);
+    ///     std::fs::write(path, bytes)?;
+    ///
+    ///     assert!(Format::decode(path)?.check().is_err());
+    ///
+    ///     let (decoding, dropped) = Format::decode_lossy(path)?;
+    ///     decoding.check()?;
+    ///     assert_eq!(dropped, 1);
+    ///     assert_eq!(decoding.data, vec![1u8; 4]);
+    ///     assert_eq!(u32::from_le_bytes(decoding.data_size), 4);
+    ///     assert_eq!(u32::from_le_bytes(decoding.total_size), 40);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode_lossy<P: AsRef<Path>>(path: P) -> Result<(Self, usize), anyhow::Error> {
+        let mut format = Format::decode_with_progress(path, |_, _| {})?;
+
+        let block_alignment = usize::from(u16::from_le_bytes(format.block_alignment));
+        let dropped = if block_alignment == 0 {
+            0
+        } else {
+            format.data.len() % block_alignment
+        };
+        format.data.truncate(format.data.len() - dropped);
+        format.data_size = u32::try_from(format.data.len())?.to_le_bytes();
+        format.sync_total_size()?;
+
+        Ok((format, dropped))
+    }
+
+    /// `decode_bytes` decodes WAVE PCM file from an in-memory buffer.
+    ///
+    /// Unlike [`Format::decode`], this function does not need the `std` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Contents of the WAV PCM file.
+    ///
+    /// # Errors
+    ///
+    /// If the header is truncated or the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     let decoding = Format::decode_bytes(&encoding.to_bytes()?)?;
+    ///     decoding.check()?;
+    ///     assert_eq!(decoding.data, encoding.data);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// A corrupt chunk size larger than the file only yields the bytes actually present:
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut bytes = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+    ///     bytes[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+    ///
+    ///     let decoding = Format::decode_bytes(&bytes)?;
+    ///     assert_eq!(decoding.data, vec![1u8; 16]);
+    ///     assert!(!decoding.consistency_report().is_empty());
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// A truncated header reports the field and byte offset that could not be read:
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let bytes = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+    ///     let error = Format::decode_bytes(&bytes[..16]).err().unwrap();
+    ///     assert_eq!(
+    ///         error.to_string(),
+    ///         "Failed reading fmt_chunk_size at offset 16, got 0 of 4 bytes instead."
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// A data chunk written before the format chunk is decoded as if it followed it:
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1, 2, 3, 4], 2, 8_000, 8)?;
+    ///     let bytes = encoding.to_bytes()?;
+    ///     let reordered = [&bytes[..12], &bytes[36..], &bytes[12..36]].concat();
+    ///
+    ///     let decoding = Format::decode_bytes(&reordered)?;
+    ///     decoding.check()?;
+    ///     assert!(decoding == encoding);
+    ///     assert_eq!(decoding.to_bytes()?, bytes);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Other chunks written before the format chunk keep their position, and a file without a
+    /// format chunk is rejected:
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let plain = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+    ///     let mut bytes = [&plain[..12], b"JUNK\x04\x00\x00\x00\x00\x00\x00\x00", &plain[12..]].concat();
+    ///     let total_size = (bytes.len() as u32 - 8).to_le_bytes();
+    ///     bytes[4..8].copy_from_slice(&total_size);
+    ///
+    ///     let decoding = Format::decode_bytes(&bytes)?;
+    ///     decoding.check()?;
+    ///     assert_eq!(decoding.leading_chunks[0].tag, *b"JUNK");
+    ///     assert_eq!(decoding.to_bytes()?, bytes);
+    ///
+    ///     bytes[24..28].copy_from_slice(b"fmt\0");
+    ///     let error = Format::decode_bytes(&bytes).err().unwrap();
+    ///     assert_eq!(
+    ///         error.to_string(),
+    ///         "WAVE PCM file has no \"fmt \" chunk, got chunks \"JUNK\", \"fmt\\x00\", \"data\" instead."
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn decode_bytes(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        let mut bytes = bytes;
+        Format::decode_from(&mut bytes)
+    }
+
+    /// `open_reader` decodes WAVE PCM file from a reader and checks its structure.
+    ///
+    /// This is [`Format::decode_bytes`] followed by [`Format::check`] for network or in-memory
+    /// sources. The reader is read to the end of the `data` chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader positioned at the start of the file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be decoded or is not properly-encoded, see [`Format::check`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     let decoding = Format::open_reader(Cursor::new(encoding.to_bytes()?))?;
+    ///     assert!(decoding == encoding);
+    ///
+    ///     let mut invalid = encoding.to_bytes()?;
+    ///     invalid[8..12].copy_from_slice(b"AVI ");
+    ///     let error = Format::open_reader(Cursor::new(&invalid)).err().unwrap();
+    ///     let decoding = Format::decode_bytes(&invalid)?;
+    ///     assert_eq!(error.to_string(), decoding.check().err().unwrap().to_string());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn open_reader<R: Read>(reader: R) -> Result<Self, anyhow::Error> {
+        let format = Format::decode_from(&mut IoSource(reader))?;
+        format.check()?;
+        Ok(format)
+    }
+
+    // Decode WAVE PCM file from a byte source.
+    //
+    // # Arguments
+    //
+    // * `reader` - A reader.
+    //
+    // # Errors
+    //
+    // If the header is truncated or the value cannot fit when performing type conversion.
+    fn decode_from<T>(reader: &mut T) -> Result<Self, anyhow::Error>
+    where
+        T: ByteSource,
+    {
+        let mut offset = 0;
+        let riff_tag = read_exact(reader, "riff_tag", &mut offset)?;
+        let total_size = read_exact(reader, "total_size", &mut offset)?;
+        let wave_tag = read_exact(reader, "wave_tag", &mut offset)?;
+        let mut fmt_chunk_tag = read_exact(reader, "fmt_chunk_tag", &mut offset)?;
+        let mut fmt_chunk_size = read_exact(reader, "fmt_chunk_size", &mut offset)?;
+
+        // Rare encoders write the data chunk before the format chunk, so the chunks preceding
+        // the format chunk are buffered until the samples can be interpreted.
+        let mut preceding = Vec::new();
+        while fmt_chunk_tag != *b"fmt " {
+            let body = read_chunk_body(reader, u32::from_le_bytes(fmt_chunk_size))?;
+            offset += body.len() + body.len() % 2;
+            preceding.push((fmt_chunk_tag, fmt_chunk_size, body));
+
+            let mut chunk_header = [0_u8; 8];
+            match reader.fill(&mut chunk_header) {
+                0 => {
+                    let tags: Vec<String> = preceding
+                        .iter()
+                        .map(|(tag, _, _)| format!("\"{}\"", tag.escape_ascii()))
+                        .collect();
+                    let tags = tags.join(", ");
+                    return Err(anyhow::anyhow!(
+                        "WAVE PCM file has no \"fmt \" chunk, got chunks {tags} instead."
+                    ));
+                }
+                8 => {}
+                nbytes => {
+                    return Err(anyhow::anyhow!(
+                        "Failed reading fmt_chunk_tag at offset {offset}, got {nbytes} of 8 bytes instead."
+                    ));
+                }
+            }
+            offset += 8;
+            fmt_chunk_tag = [
+                chunk_header[0],
+                chunk_header[1],
+                chunk_header[2],
+                chunk_header[3],
+            ];
+            fmt_chunk_size = [
+                chunk_header[4],
+                chunk_header[5],
+                chunk_header[6],
+                chunk_header[7],
+            ];
+        }
+
+        let fmt_code = read_exact(reader, "fmt_code", &mut offset)?;
+        let num_channels = read_exact(reader, "num_channels", &mut offset)?;
+        let sampling_rate = read_exact(reader, "sampling_rate", &mut offset)?;
+        let byte_rate = read_exact(reader, "byte_rate", &mut offset)?;
+        let block_alignment = read_exact(reader, "block_alignment", &mut offset)?;
+        let bits_per_sample = read_exact(reader, "bits_per_sample", &mut offset)?;
+        let extension_size = u32::from_le_bytes(fmt_chunk_size).saturating_sub(16);
+        let fmt_extension = read_chunk_body(reader, extension_size)?;
+        if fmt_extension.len() < extension_size.try_into()? {
+            let nbytes = fmt_extension.len();
+            return Err(anyhow::anyhow!(
+                "Failed reading fmt_extension at offset {offset}, got {nbytes} of {extension_size} bytes instead."
+            ));
+        }
+
+        let mut format = Format {
+            riff_tag,
+            total_size,
+            wave_tag,
+            fmt_chunk_tag,
+            fmt_chunk_size,
+            fmt_code,
+            num_channels,
+            sampling_rate,
+            byte_rate,
+            block_alignment,
+            bits_per_sample,
+            fmt_extension,
+            data_tag: [0; 4],
+            data_size: [0; 4],
+            data: Vec::new(),
+            sample_loops: Vec::new(),
+            sampler: None,
+            broadcast_info: None,
+            instrument: None,
+            cue_points: Vec::new(),
+            leading_chunks: Vec::new(),
+            trailing_chunks: Vec::new(),
+            chunk_order: Vec::new(),
+            file_size: None,
+        };
+        let fmt_position = preceding.len();
+        for (tag, size, body) in preceding {
+            format.add_chunk(tag, size, body);
+        }
+        loop {
+            let tag = read4(reader);
+            let mut size = [0_u8; 4];
+            if reader.fill(&mut size) < 4 {
+                break;
+            }
+            let body = read_chunk_body(reader, u32::from_le_bytes(size))?;
+            format.add_chunk(tag, size, body);
+        }
+        format.settle_chunk_order(fmt_position)?;
+
+        Ok(format)
+    }
+
+    // Record the format chunk in the chunk order of a decoded file, and drop the order if it is
+    // the one written by default.
+    //
+    // A data chunk preceding the format chunk is written after it.
+    //
+    // # Arguments
+    //
+    // * `fmt_position` - Number of chunks preceding the format chunk in the file.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn settle_chunk_order(&mut self, fmt_position: usize) -> Result<(), anyhow::Error> {
+        let fmt_position = self
+            .chunk_order
+            .iter()
+            .position(|&kind| kind == ChunkKind::Data)
+            .map_or(fmt_position, |position| position.min(fmt_position));
+        self.chunk_order.insert(fmt_position, ChunkKind::Fmt);
+        if self.chunk_order == self.default_chunk_order()? {
+            self.chunk_order.clear();
+        }
+
+        Ok(())
+    }
+
+    // Store a decoded chunk in the field modeling it.
+    //
+    // The first `data` chunk holds the audio data. Chunks that cannot be modeled are kept
+    // verbatim, before or after the audio data depending on where they were found. The kind of
+    // every chunk is recorded in file order.
+    //
+    // # Arguments
+    //
+    // * `tag` - Chunk tag.
+    // * `size` - Chunk size as found in the file.
+    // * `body` - Contents of the chunk without the tag, size and alignment byte.
+    fn add_chunk(&mut self, tag: [u8; 4], size: [u8; 4], body: Vec<u8>) {
+        let modeled = match &tag {
+            b"data" if self.data_tag != *b"data" => {
+                self.data_tag = tag;
+                self.data_size = size;
+                self.data = body;
+                self.chunk_order.push(ChunkKind::Data);
+                return;
+            }
+            b"smpl" if self.sampler.is_none() => {
+                Sampler::parse(&body).map(|(sampler, sample_loops)| {
+                    self.sampler = Some(sampler);
+                    self.sample_loops = sample_loops;
+                    ChunkKind::Smpl
+                })
+            }
+            b"bext" if self.broadcast_info.is_none() => {
+                self.broadcast_info = BroadcastInfo::parse(&body);
+                self.broadcast_info.as_ref().map(|_| ChunkKind::Bext)
+            }
+            b"inst" if self.instrument.is_none() => {
+                self.instrument = Instrument::parse(&body);
+                self.instrument.map(|_| ChunkKind::Inst)
+            }
+            b"cue " if self.cue_points.is_empty() => {
+                self.cue_points = CuePoint::parse(&body);
+                (!self.cue_points.is_empty()).then_some(ChunkKind::Cue)
+            }
+            b"LIST" if body.starts_with(b"adtl") => {
+                self.add_labels(&body).then_some(ChunkKind::Labels)
+            }
+            _ => None,
+        };
+
+        if let Some(kind) = modeled {
+            self.chunk_order.push(kind);
+            return;
+        }
+
+        self.chunk_order.push(ChunkKind::Unknown);
+        let chunk = Chunk { tag, data: body };
+        if self.data_tag == *b"data" {
+            self.trailing_chunks.push(chunk);
+        } else {
+            self.leading_chunks.push(chunk);
+        }
+    }
+
+    // Attach the labels of a `LIST` `adtl` chunk to the cue points decoded so far.
+    //
+    // Labels are only attached when every one of them names a distinct unlabeled cue point,
+    // otherwise the chunk is left for the caller to keep verbatim.
+    //
+    // # Arguments
+    //
+    // * `body` - Contents of the chunk without the tag and size, starting with "adtl".
+    fn add_labels(&mut self, body: &[u8]) -> bool {
+        let Some(labels) = CuePoint::parse_labels(body) else {
+            return false;
+        };
+
+        let mut cue_points = self.cue_points.clone();
+        for (id, label) in labels.iter().cloned() {
+            match cue_points.iter_mut().find(|cue_point| cue_point.id == id) {
+                Some(cue_point) if cue_point.label.is_none() => cue_point.label = Some(label),
+                _ => return false,
+            }
+        }
+
+        self.cue_points = cue_points;
+        !labels.is_empty()
+    }
+
+    /// `check` checks if the WAVE PCM structure is properly-encoded.
+    ///
+    /// Every format the crate decodes is accepted: integer PCM (format code 1), IEEE float (3),
+    /// A-law (6) and mu-law (7). Companded audio data can be expanded with
+    /// [`Format::decode_companded`]. The format chunk holds 16 bytes, 18 bytes ending in
+    /// `cbSize = 0`, or 40 bytes of the extensible format (65534) with one of these formats as
+    /// the sub-format, as written by [`Format::encode_sample_format`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`](https://docs.rs/core/*/core/result/enum.Result.html) if the slice is not
+    /// UTF-8 with a description as to why the provided bytes are not UTF-8. The vector you moved
+    /// in is also included.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let decoding = Format::decode("sample.wav")?;
+    ///     decoding.check()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// A header claiming more than [`MAX_NUM_CHANNELS`] channels is rejected:
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     encoding.num_channels = 10_000_u16.to_le_bytes();
+    ///     assert!(encoding.check().is_err());
+    ///     assert!(Format::encode(vec![0u8; 16], 10_000, 16_000, 16).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn check(&self) -> Result<(), anyhow::Error> {
+        self.check_impl(false)
+    }
+
+    /// `check_allow_empty` checks if the WAVE PCM structure is properly-encoded, accepting a file
+    /// without audio data.
+    ///
+    /// See [`Format::check`], which rejects header-only files.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`](https://docs.rs/core/*/core/result/enum.Result.html) if the slice is not
+    /// UTF-8 with a description as to why the provided bytes are not UTF-8. The vector you moved
+    /// in is also included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(Vec::new(), 1, 16_000, 16)?;
+    ///     assert!(encoding.check().is_err());
+    ///     encoding.check_allow_empty()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn check_allow_empty(&self) -> Result<(), anyhow::Error> {
+        self.check_impl(true)
+    }
+
+    // Check if the WAVE PCM structure is properly-encoded.
+    //
+    // # Arguments
+    //
+    // * `allow_empty` - Whether a file without audio data is accepted.
+    //
+    // # Errors
+    //
+    // If a field does not hold the value required by the format.
+    fn check_impl(&self, allow_empty: bool) -> Result<(), anyhow::Error> {
+        let riff_tag_val = String::from_utf8(self.riff_tag.to_vec())?;
+        if riff_tag_val != "RIFF" {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires string \"RIFF\" as bytes 1 - 4, got {riff_tag_val} instead."
+            ));
+        }
+
+        let total_size_len = self.total_size.len();
+        if total_size_len != 4 {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires 4 bytes as bytes 5 - 8, got {total_size_len} instead."
+            ));
+        }
+
+        let wave_tag_val = String::from_utf8(self.wave_tag.to_vec())?;
+        if wave_tag_val != "WAVE" {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires string \"WAVE\" as bytes 9 - 12, got {wave_tag_val} instead."
+            ));
+        }
+
+        let fmt_chunk_tag_val = String::from_utf8(self.fmt_chunk_tag.to_vec())?;
+        if fmt_chunk_tag_val != "fmt " {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires string \"fmt \" as bytes 13 - 16, got {fmt_chunk_tag_val} instead."
+            ));
+        }
+
+        self.check_fmt_chunk()?;
+
+        let num_channels_len = self.num_channels.len();
+        if num_channels_len != 2 {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires 2 bytes as bytes 23 - 24, got {num_channels_len} instead."
+            ));
+        }
+
+        let num_channels_val = u16::from_le_bytes(self.num_channels);
+        if num_channels_val > MAX_NUM_CHANNELS {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires at most {MAX_NUM_CHANNELS} channels as bytes 23 - 24, got {num_channels_val} instead."
+            ));
+        }
+
+        let sampling_rate_len = self.sampling_rate.len();
+        if sampling_rate_len != 4 {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires 4 bytes as bytes 25 - 28, got {sampling_rate_len} instead."
+            ));
+        }
+
+        let byte_rate_len = self.byte_rate.len();
+        if byte_rate_len != 4 {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires 4 bytes as bytes 29 - 32, got {byte_rate_len} instead."
+            ));
+        }
+
+        let block_alignment_len = self.block_alignment.len();
+        if block_alignment_len != 2 {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires 2 bytes as bytes 33 - 34, got {block_alignment_len} instead."
+            ));
+        }
+
+        let bits_per_sample_len = self.bits_per_sample.len();
+        if bits_per_sample_len != 2 {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires 2 bytes as bytes 35 - 36, got {bits_per_sample_len} instead."
+            ));
+        }
+        self.require_byte_aligned()?;
+
+        let data_tag_val = String::from_utf8(self.data_tag.to_vec())?;
+        if data_tag_val != "data" {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires string \"data\" as bytes 37 - 40, got {data_tag_val} instead."
+            ));
+        }
+
+        let data_size_len = self.data_size.len();
+        if data_size_len != 4 {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires 4 bytes as bytes 41 - 44, got {data_size_len} instead."
+            ));
+        }
+
+        let block_alignment_val = usize::from(u16::from_le_bytes(self.block_alignment));
+        if block_alignment_val != 0 && !self.data.len().is_multiple_of(block_alignment_val) {
+            let data_len = self.data.len();
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires a multiple of {block_alignment_val} bytes as bytes 45 - EOF, got {data_len} instead."
+            ));
+        }
+
+        if self.data.is_empty() && !allow_empty {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires at least one bytes as bytes 45 - EOF, got 0 instead."
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `info` prints the information about the WAVE PCM file.
+    ///
+    /// The output is that of [`Format::summary`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`](https://docs.rs/core/*/core/result/enum.Result.html) if the slice is not
+    /// UTF-8 with a description as to why the provided bytes are not UTF-8. The vector you moved
+    /// in is also included.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let decoding = Format::decode("sample.wav")?;
+    ///     decoding.check()?;
+    ///     decoding.info()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn info(&self) -> Result<(), anyhow::Error> {
+        println!("{}", self.summary()?);
+
+        Ok(())
+    }
+
+    /// `summary` returns the information about the WAVE PCM file printed by [`Format::info`].
+    ///
+    /// Next to the stored byte rate it lists the byte rate recomputed as `sampling_rate *
+    /// block_alignment`, and marks the stored value with `(MISMATCH)` when the two differ.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`](https://docs.rs/core/*/core/result/enum.Result.html) if the slice is not
+    /// UTF-8 with a description as to why the provided bytes are not UTF-8. The vector you moved
+    /// in is also included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0u8; 16], 2, 44_100, 16)?;
+    ///     let summary = encoding.summary()?;
+    ///     assert!(summary.contains("BYTERATE:           176400\n"));
+    ///     assert!(summary.contains("COMPUTED BYTERATE:  176400\n"));
+    ///     assert!(!summary.contains("MISMATCH"));
+    ///
+    ///     encoding.byte_rate = 88_200_u32.to_le_bytes();
+    ///     let summary = encoding.summary()?;
+    ///     assert!(summary.contains("BYTERATE:           88200 (MISMATCH)\n"));
+    ///     assert!(summary.contains("COMPUTED BYTERATE:  176400\n"));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn summary(&self) -> Result<String, anyhow::Error> {
+        let riff_tag = String::from_utf8(self.riff_tag.to_vec())?;
+        let total_size = u32::from_le_bytes(self.total_size);
+        let wave_tag = String::from_utf8(self.wave_tag.to_vec())?;
+        let fmt_chunk_tag = String::from_utf8(self.fmt_chunk_tag.to_vec())?;
+        let fmt_chunk_size = u32::from_le_bytes(self.fmt_chunk_size);
+        let fmt_code = u16::from_le_bytes(self.fmt_code);
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        let sampling_rate = u32::from_le_bytes(self.sampling_rate);
+        let byte_rate = u32::from_le_bytes(self.byte_rate);
+        let block_alignment = u16::from_le_bytes(self.block_alignment);
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        let data_tag = String::from_utf8(self.data_tag.to_vec())?;
+        let data_size = u32::from_le_bytes(self.data_size);
+
+        // Computed in u64 so a bogus header cannot overflow.
+        let computed_byte_rate = u64::from(sampling_rate) * u64::from(block_alignment);
+        let mismatch = if u64::from(byte_rate) == computed_byte_rate {
+            ""
+        } else {
+            " (MISMATCH)"
+        };
+
+        Ok(format!(
+            "RIFF TAG:           {riff_tag:?}\n\
+             TOTAL SIZE:         {total_size:?}\n\
+             WAVE TAG:           {wave_tag:?}\n\
+             FMT CHUNK TAG:      {fmt_chunk_tag:?}\n\
+             FMT CHUNK SIZE:     {fmt_chunk_size:?}\n\
+             FMT CODE:           {fmt_code:?}\n\
+             CHANNELS:           {num_channels:?}\n\
+             SAMPLING RATE:      {sampling_rate:?}\n\
+             BYTERATE:           {byte_rate:?}{mismatch}\n\
+             COMPUTED BYTERATE:  {computed_byte_rate:?}\n\
+             BLOCK ALIGNMENT:    {block_alignment:?}\n\
+             BITS PER SAMPLE:    {bits_per_sample:?}\n\
+             DATA TAG:           {data_tag:?}\n\
+             DATA SIZE:          {data_size:?}\n"
+        ))
+    }
+
+    /// `describe` summarizes the file in a single line, e.g. `"WAV 16kHz 2ch 16bit 3.50s"`.
+    ///
+    /// This is lighter than [`Format::info`] and suits log lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 224_000], 2, 16_000, 16)?;
+    ///     assert_eq!(encoding.describe(), "WAV 16kHz 2ch 16bit 3.50s");
+    ///
+    ///     let encoding = Format::encode(vec![0u8; 44_100], 1, 44_100, 8)?;
+    ///     assert_eq!(encoding.describe(), "WAV 44.1kHz 1ch 8bit 1.00s");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn describe(&self) -> String {
+        let sampling_rate = u32::from_le_bytes(self.sampling_rate);
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        let seconds = if sampling_rate == 0 {
+            0.0
+        } else {
+            self.frame_count() as f64 / f64::from(sampling_rate)
+        };
+        let khz = f64::from(sampling_rate) / 1_000.0;
+
+        format!("WAV {khz}kHz {num_channels}ch {bits_per_sample}bit {seconds:.2}s")
+    }
+
+    /// `csv_header` returns the header line matching the columns of [`Format::csv_row`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// assert_eq!(
+    ///     Format::csv_header(),
+    ///     "rate,channels,bits,frames,duration_secs,peak_dbfs"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn csv_header() -> &'static str {
+        "rate,channels,bits,frames,duration_secs,peak_dbfs"
+    }
+
+    /// `csv_row` summarizes the file as one comma-separated line for batch reports.
+    ///
+    /// Columns follow [`Format::csv_header`]. The duration has six decimals and the peak level
+    /// two, digital silence reads `-inf`.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [16_384_i16, -8_000, 0, 0].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let row = encoding.csv_row()?;
+    ///     assert_eq!(row, "16000,2,16,2,0.000125,-6.02");
+    ///     assert_eq!(
+    ///         row.split(',').count(),
+    ///         Format::csv_header().split(',').count()
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn csv_row(&self) -> Result<String, anyhow::Error> {
+        let sampling_rate = u32::from_le_bytes(self.sampling_rate);
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        let frames = self.frame_count();
+        let seconds = if sampling_rate == 0 {
+            0.0
+        } else {
+            frames as f64 / f64::from(sampling_rate)
+        };
+        let peak_dbfs = self.peak_dbfs()?;
+
+        Ok(format!(
+            "{sampling_rate},{num_channels},{bits_per_sample},{frames},{seconds:.6},{peak_dbfs:.2}"
+        ))
+    }
+
+    /// `write` writes a WAVE PCM file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the WAV PCM file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of
+    /// non-[`ErrorKind::Interrupted`](https://docs.rs/std/*/std/io/error/enum.ErrorKind.html) kind
+    /// that [`write`](https://docs.rs/std/*/std/io/trait.Write.html#tymethod.write) returns.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let decoding = Format::decode("sample.wav")?;
+    ///     decoding.write("sample_copy.wav");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write(&self, path: &str) -> Result<(), anyhow::Error> {
+        self.write_with_progress(path, |_, _| {})
+    }
+
+    /// `write_with_progress` writes a WAVE PCM file, reporting progress while writing.
+    ///
+    /// The audio data is written and flushed in blocks of 1 MiB, so huge files reach the disk
+    /// incrementally. After every block the callback receives the number of bytes written so
+    /// far and the file size; the last call always reports completion, exactly once.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the WAV PCM file.
+    /// * `on_progress` - Callback receiving `(bytes_written, total_bytes)`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of
+    /// non-[`ErrorKind::Interrupted`](https://docs.rs/std/*/std/io/error/enum.ErrorKind.html) kind
+    /// that [`write`](https://docs.rs/std/*/std/io/trait.Write.html#tymethod.write) returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_write_with_progress.wav");
+    ///     let data: Vec<u8> = (0..5_000_000_u32).map(|n| (n % 251) as u8).collect();
+    ///     let encoding = Format::encode(data, 2, 48_000, 16)?;
+    ///
+    ///     let mut reports = Vec::new();
+    ///     encoding.write_with_progress(&path, |written, total| reports.push((written, total)))?;
+    ///     assert_eq!(reports.len(), 5);
+    ///     assert_eq!(reports.last(), Some(&(5_000_044, 5_000_044)));
+    ///
+    ///     let mut reports = Vec::new();
+    ///     let empty = Format::encode(Vec::new(), 2, 48_000, 16)?;
+    ///     empty.write_with_progress(&path, |written, total| reports.push((written, total)))?;
+    ///     assert_eq!(reports, vec![(44, 44)]);
+    ///
+    ///     encoding.write(path.to_str().unwrap())?;
+    ///     let decoding = Format::decode(path.to_str().unwrap())?;
+    ///     assert!(decoding == encoding);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_with_progress<P, F>(
+        &self,
+        path: P,
+        mut on_progress: F,
+    ) -> Result<(), anyhow::Error>
+    where
+        P: AsRef<Path>,
+        F: FnMut(u64, u64),
+    {
+        let header = self.header()?;
+        let trailer = self.trailer()?;
+        let total_bytes = (header.len() + self.data.len() + trailer.len()) as u64;
+
+        let file = File::create(path)?;
+        let mut bufw = BufWriter::new(file);
+
+        bufw.write_all(&header)?;
+        let mut bytes_written = header.len() as u64;
+        for block in self.data.chunks(WRITE_BLOCK) {
+            bufw.write_all(block)?;
+            bufw.flush()?;
+            bytes_written += block.len() as u64;
+            on_progress(bytes_written, total_bytes);
+        }
+        bufw.write_all(&trailer)?;
+        bufw.flush()?;
+        if self.data.is_empty() || bytes_written != total_bytes {
+            on_progress(total_bytes, total_bytes);
+        }
+
+        Ok(())
+    }
+
+    /// `write_raw` writes the audio data without any RIFF header or chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - A writer.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of
+    /// non-[`ErrorKind::Interrupted`](https://docs.rs/std/*/std/io/error/enum.ErrorKind.html) kind
+    /// that [`write`](https://docs.rs/std/*/std/io/trait.Write.html#tymethod.write) returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1, 2, 3, 4], 1, 16_000, 16)?;
+    ///     let mut raw = Vec::new();
+    ///     encoding.write_raw(&mut raw)?;
+    ///     assert_eq!(raw, vec![1, 2, 3, 4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_raw<W: Write>(&self, writer: &mut W) -> Result<(), anyhow::Error> {
+        writer.write_all(&self.data)?;
+        Ok(())
+    }
+
+    /// `write_raw_file` writes the audio data to a headerless PCM file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path to the raw PCM file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of
+    /// non-[`ErrorKind::Interrupted`](https://docs.rs/std/*/std/io/error/enum.ErrorKind.html) kind
+    /// that [`write`](https://docs.rs/std/*/std/io/trait.Write.html#tymethod.write) returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_write_raw_file.pcm");
+    ///     let encoding = Format::encode(vec![1, 2, 3, 4, 5, 6], 1, 16_000, 16)?;
+    ///     encoding.write_raw_file(&path)?;
+    ///
+    ///     let raw = std::fs::read(&path)?;
+    ///     assert_eq!(raw.len(), encoding.data.len());
+    ///     assert_eq!(raw, encoding.data);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_raw_file<P: AsRef<Path>>(&self, path: P) -> Result<(), anyhow::Error> {
+        let mut bufw = BufWriter::new(File::create(path)?);
+        self.write_raw(&mut bufw)?;
+        bufw.flush()?;
+        Ok(())
+    }
+
+    /// `to_bytes` encodes the WAVE PCM file into an in-memory buffer.
+    ///
+    /// Unlike [`Format::write`], this function does not need the `std` feature.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     assert_eq!(encoding.to_bytes()?.len(), 44 + 16);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, anyhow::Error> {
+        let mut bytes = self.header()?;
+        bytes.extend_from_slice(&self.data);
+        bytes.extend_from_slice(&self.trailer()?);
+        Ok(bytes)
+    }
+
+    /// `is_roundtrip_stable` checks that encoding the file and decoding it back yields an equal
+    /// file.
+    ///
+    /// Files built by this crate are stable, a file edited field by field may not be, e.g. when
+    /// `data_size` no longer matches the audio data. A file that fails to decode is not stable.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![1u8; 16], 2, 16_000, 16)?;
+    ///     assert!(encoding.is_roundtrip_stable()?);
+    ///
+    ///     encoding.data.extend_from_slice(&[2u8; 4]);
+    ///     assert!(!encoding.is_roundtrip_stable()?);
+    ///
+    ///     let resized = encoding.with_data(encoding.data.clone())?;
+    ///     assert!(resized.is_roundtrip_stable()?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn is_roundtrip_stable(&self) -> Result<bool, anyhow::Error> {
+        let bytes = self.to_bytes()?;
+        Ok(Format::decode_bytes(&bytes).is_ok_and(|decoded| decoded == *self))
+    }
+
+    /// `into_reader` encodes WAVE PCM file into a seekable in-memory stream.
+    ///
+    /// The result implements `Read` and `Seek`, so the file can be handed to code expecting a
+    /// WAV stream, such as [`Decoder`].
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Decoder, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 16], 2, 16_000, 16)?;
+    ///     assert!(Format::open_reader(encoding.clone().into_reader()?)? == encoding);
+    ///
+    ///     let mut decoder = Decoder::new(encoding.into_reader()?)?;
+    ///     decoder.seek_to_frame(3)?;
+    ///     assert_eq!(decoder.next(), Some(vec![1u8; 4]));
+    ///     assert_eq!(decoder.next(), None);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn into_reader(self) -> Result<std::io::Cursor<Vec<u8>>, anyhow::Error> {
+        Ok(std::io::Cursor::new(self.to_bytes()?))
+    }
+
+    /// `header_bytes` encodes everything preceding the audio data.
+    ///
+    /// This is 44 bytes for a canonical file, and longer when the format chunk is extended or
+    /// chunks precede the audio data. Servers can write the header and then stream the audio
+    /// data separately (e.g. with `sendfile`); chunks following the audio data, if any, come
+    /// from [`Format::trailer_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     let header = encoding.header_bytes()?;
+    ///     assert_eq!(header.len(), 44);
+    ///     assert_eq!(header[..], encoding.to_bytes()?[..44]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn header_bytes(&self) -> Result<Vec<u8>, anyhow::Error> {
+        self.header()
+    }
+
+    /// `trailer_bytes` encodes everything following the audio data.
+    ///
+    /// This is empty unless chunks follow the audio data, see [`Format::header_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     assert!(encoding.trailer_bytes()?.is_empty());
+    ///
+    ///     encoding.set_metadata("INAM", "Title")?;
+    ///     let bytes = [encoding.header_bytes()?, encoding.data.clone(), encoding.trailer_bytes()?];
+    ///     assert_eq!(bytes.concat(), encoding.to_bytes()?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn trailer_bytes(&self) -> Result<Vec<u8>, anyhow::Error> {
+        self.trailer()
+    }
+
+    // Return every field and chunk preceding the audio data as bytes.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn header(&self) -> Result<Vec<u8>, anyhow::Error> {
+        let (chunk_order, _) = self.chunk_order_sections();
+        let mut bytes = [&self.riff_tag[..], &self.total_size, &self.wave_tag].concat();
+        bytes.extend(in_chunk_order(self.header_chunks()?, chunk_order).concat());
+        bytes.extend_from_slice(&self.data_tag);
+        bytes.extend_from_slice(&self.data_size);
+
+        Ok(bytes)
+    }
+
+    // Return every chunk following the audio data as bytes, including the alignment byte.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn trailer(&self) -> Result<Vec<u8>, anyhow::Error> {
+        let (_, chunk_order) = self.chunk_order_sections();
+        let mut bytes = in_chunk_order(self.trailer_chunks()?, chunk_order).concat();
+        if !bytes.is_empty() && self.data.len() % 2 == 1 {
+            bytes.insert(0, 0);
+        }
+
+        Ok(bytes)
+    }
+
+    // Return the chunks preceding the audio data with their kinds, in the order written by
+    // default.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn header_chunks(&self) -> Result<Vec<(ChunkKind, Vec<u8>)>, anyhow::Error> {
+        let mut fmt_chunk = [
+            &self.fmt_chunk_tag[..],
+            &self.fmt_chunk_size,
+            &self.fmt_code,
+            &self.num_channels,
+            &self.sampling_rate,
+            &self.byte_rate,
+            &self.block_alignment,
+            &self.bits_per_sample,
+            &self.fmt_extension,
+        ]
+        .concat();
+        if self.fmt_extension.len() % 2 == 1 {
+            fmt_chunk.push(0);
+        }
+
+        let mut chunks = vec![(ChunkKind::Fmt, fmt_chunk)];
+        if let Some(broadcast_info) = &self.broadcast_info {
+            chunks.push((ChunkKind::Bext, broadcast_info.chunk().to_bytes()?));
+        }
+        for chunk in &self.leading_chunks {
+            chunks.push((ChunkKind::Unknown, chunk.to_bytes()?));
+        }
+
+        Ok(chunks)
+    }
+
+    // Return the chunks following the audio data with their kinds, in the order written by
+    // default.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn trailer_chunks(&self) -> Result<Vec<(ChunkKind, Vec<u8>)>, anyhow::Error> {
+        let mut chunks = Vec::new();
+        for chunk in &self.trailing_chunks {
+            chunks.push((ChunkKind::Unknown, chunk.to_bytes()?));
+        }
+        if let Some(sampler) = &self.sampler {
+            chunks.push((
+                ChunkKind::Smpl,
+                sampler.chunk(&self.sample_loops)?.to_bytes()?,
+            ));
+        } else if !self.sample_loops.is_empty() {
+            let sampler = Sampler::new(u32::from_le_bytes(self.sampling_rate));
+            chunks.push((
+                ChunkKind::Smpl,
+                sampler.chunk(&self.sample_loops)?.to_bytes()?,
+            ));
+        }
+        if let Some(instrument) = &self.instrument {
+            chunks.push((ChunkKind::Inst, instrument.chunk().to_bytes()?));
+        }
+        if !self.cue_points.is_empty() {
+            let kinds = [ChunkKind::Cue, ChunkKind::Labels];
+            for (kind, chunk) in kinds.into_iter().zip(CuePoint::chunks(&self.cue_points)?) {
+                chunks.push((kind, chunk.to_bytes()?));
+            }
+        }
+
+        Ok(chunks)
+    }
+
+    // Return the kinds of every chunk in the order written by default.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn default_chunk_order(&self) -> Result<Vec<ChunkKind>, anyhow::Error> {
+        let mut chunk_order: Vec<ChunkKind> = self
+            .header_chunks()?
+            .into_iter()
+            .map(|(kind, _)| kind)
+            .collect();
+        chunk_order.push(ChunkKind::Data);
+        chunk_order.extend(self.trailer_chunks()?.into_iter().map(|(kind, _)| kind));
+        Ok(chunk_order)
+    }
+
+    // Split the recorded chunk order into the kinds preceding and following the data chunk.
+    fn chunk_order_sections(&self) -> (&[ChunkKind], &[ChunkKind]) {
+        match self
+            .chunk_order
+            .iter()
+            .position(|&kind| kind == ChunkKind::Data)
+        {
+            Some(position) => (
+                &self.chunk_order[..position],
+                &self.chunk_order[position + 1..],
+            ),
+            None => (&self.chunk_order, &[]),
+        }
+    }
+
+    /// `set_sample_loops` replaces the loop points of the sampler (`smpl`) chunk.
+    ///
+    /// The chunk-level fields in `sampler` are kept, or set to [`Sampler::new`] if the file has
+    /// no sampler chunk yet. The `total_size` field is updated to account for the chunk, an empty
+    /// list removes it unless `sampler` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_loops` - Loop points.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleLoop, Sampler};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0u8; 64], 1, 16_000, 16)?;
+    ///     let sample_loop = SampleLoop::new(4, 27);
+    ///     encoding.set_sample_loops(vec![sample_loop])?;
+    ///
+    ///     let decoding = Format::decode_bytes(&encoding.to_bytes()?)?;
+    ///     decoding.check()?;
+    ///     assert_eq!(decoding.sample_loops, vec![sample_loop]);
+    ///     assert_eq!(decoding.data, encoding.data);
+    ///
+    ///     // Sampler fields and loop play counts survive a round trip byte for byte.
+    ///     encoding.sampler = Some(Sampler {
+    ///         manufacturer: 0x47,
+    ///         product: 0x5E,
+    ///         midi_unity_note: 57,
+    ///         midi_pitch_fraction: 0x8000_0000,
+    ///         smpte_format: 25,
+    ///         smpte_offset: 0x0102_0304,
+    ///         sampler_data: vec![1, 2, 3, 4],
+    ///         ..Sampler::new(16_000)
+    ///     });
+    ///     encoding.set_sample_loops(vec![SampleLoop { cue_point_id: 7, play_count: 3, ..sample_loop }])?;
+    ///     let bytes = encoding.to_bytes()?;
+    ///     let decoding = Format::decode_bytes(&bytes)?;
+    ///     decoding.check()?;
+    ///     assert!(decoding == encoding);
+    ///     assert_eq!(decoding.to_bytes()?, bytes);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_sample_loops(&mut self, sample_loops: Vec<SampleLoop>) -> Result<(), anyhow::Error> {
+        if !sample_loops.is_empty() && self.sampler.is_none() {
+            self.sampler = Some(Sampler::new(u32::from_le_bytes(self.sampling_rate)));
+        }
+        self.sample_loops = sample_loops;
+        self.sync_total_size()
+    }
+
+    /// `set_cue_points` replaces the cue points of the `cue ` chunk and their labels.
+    ///
+    /// Labels are written as `labl` entries of a `LIST` `adtl` chunk following the `cue ` chunk.
+    /// The `total_size` field is updated to account for the chunks, an empty list removes them.
+    ///
+    /// # Arguments
+    ///
+    /// * `cue_points` - Cue points.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{CuePoint, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0u8; 64], 1, 16_000, 16)?;
+    ///     let cue_points = vec![
+    ///         CuePoint::new(1, 12, Some("Chorus".to_string())),
+    ///         CuePoint::new(2, 30, None),
+    ///     ];
+    ///     encoding.set_cue_points(cue_points.clone())?;
+    ///
+    ///     let bytes = encoding.to_bytes()?;
+    ///     let decoding = Format::decode_bytes(&bytes)?;
+    ///     decoding.check()?;
+    ///     assert_eq!(decoding.cue_points, cue_points);
+    ///     assert!(decoding.trailing_chunks.is_empty());
+    ///     assert_eq!(decoding.to_bytes()?, bytes);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_cue_points(&mut self, cue_points: Vec<CuePoint>) -> Result<(), anyhow::Error> {
+        self.cue_points = cue_points;
+        self.sync_total_size()
+    }
+
+    /// `set_broadcast_info` replaces the Broadcast Wave Format metadata of the `bext` chunk.
+    ///
+    /// The `total_size` field is updated to account for the chunk, `None` removes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `broadcast_info` - Broadcast Wave Format metadata.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{BroadcastInfo, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0u8; 64], 1, 48_000, 16)?;
+    ///     let broadcast_info = BroadcastInfo {
+    ///         description: "Evening news".to_string(),
+    ///         originator: "wavepcm".to_string(),
+    ///         originator_reference: String::new(),
+    ///         origination_date: "2024-01-31".to_string(),
+    ///         origination_time: "18-30-00".to_string(),
+    ///         time_reference: 3_196_800_000,
+    ///         remainder: Vec::new(),
+    ///     };
+    ///     encoding.set_broadcast_info(Some(broadcast_info))?;
+    ///
+    ///     let decoding = Format::decode_bytes(&encoding.to_bytes()?)?;
+    ///     let decoded = decoding.broadcast_info.unwrap();
+    ///     assert_eq!(decoded.time_reference, 3_196_800_000);
+    ///     assert_eq!(decoded.description, "Evening news");
+    ///     assert_eq!(decoding.data, encoding.data);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_broadcast_info(
+        &mut self,
+        broadcast_info: Option<BroadcastInfo>,
+    ) -> Result<(), anyhow::Error> {
+        self.broadcast_info = broadcast_info;
+        self.sync_total_size()
+    }
+
+    // Recompute the `total_size` field from the chunks that will be written.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    fn sync_total_size(&mut self) -> Result<(), anyhow::Error> {
+        let size = self.header()?.len() - 8 + self.data.len() + self.trailer()?.len();
+        let size: u32 = size.try_into()?;
+        self.total_size = size.to_le_bytes();
+        self.file_size = None;
+        Ok(())
+    }
+
+    /// `set_instrument` replaces the playback parameters of the instrument (`inst`) chunk.
+    ///
+    /// The `total_size` field is updated to account for the chunk, `None` removes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `instrument` - Playback parameters.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, Instrument};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0u8; 64], 1, 44_100, 16)?;
+    ///     let instrument = Instrument {
+    ///         unshifted_note: 57,
+    ///         fine_tune: -12,
+    ///         gain: -3,
+    ///         low_note: 48,
+    ///         high_note: 64,
+    ///         low_velocity: 1,
+    ///         high_velocity: 127,
+    ///     };
+    ///     encoding.set_instrument(Some(instrument))?;
+    ///
+    ///     let bytes = encoding.to_bytes()?;
+    ///     assert_eq!(u32::from_le_bytes(encoding.total_size) as usize, bytes.len() - 8);
+    ///     let decoding = Format::decode_bytes(&bytes)?;
+    ///     decoding.check()?;
+    ///     assert_eq!(decoding.instrument, Some(instrument));
+    ///     assert_eq!(decoding.instrument.map(|instrument| instrument.unshifted_note), Some(57));
+    ///     assert!(decoding.trailing_chunks.is_empty());
+    ///     assert_eq!(decoding.to_bytes()?, bytes);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_instrument(&mut self, instrument: Option<Instrument>) -> Result<(), anyhow::Error> {
+        self.instrument = instrument;
+        self.sync_total_size()
+    }
+
+    /// `copy_metadata_from` copies the descriptive metadata chunks from another file.
+    ///
+    /// The `LIST`, `bext`, `cue ` and `smpl` chunks of `src`, modeled or kept verbatim, replace
+    /// those of this file, which keeps provenance through transforms that build a new file from
+    /// scratch. Other chunks, such as `fact` or `PEAK`, describe the audio data of `src` and
+    /// are not copied.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - File to copy the chunks from.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Chunk, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut source = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     source.set_metadata("INAM", "Title")?;
+    ///     source.set_metadata("IART", "Artist")?;
+    ///
+    ///     let reversed = source.data.iter().rev().copied().collect();
+    ///     let mut output = Format::encode(reversed, 1, 16_000, 16)?;
+    ///     output.set_metadata("INAM", "Other")?;
+    ///     output.copy_metadata_from(&source)?;
+    ///
+    ///     output.check()?;
+    ///     for key in ["INAM", "IART"] {
+    ///         assert_eq!(output.metadata(key), source.metadata(key));
+    ///     }
+    ///     assert_eq!(output.trailing_chunks, source.trailing_chunks);
+    ///
+    ///     // The frame count of the `fact` chunk goes stale once the audio data changes.
+    ///     source.leading_chunks.push(Chunk { tag: *b"fact", data: 8_u32.to_le_bytes().to_vec() });
+    ///     let resampled = source.resample(8_000)?;
+    ///     resampled.check()?;
+    ///     assert_eq!(resampled.frame_count(), 4);
+    ///     assert!(resampled.leading_chunks.is_empty());
+    ///     assert_eq!(resampled.metadata("INAM"), source.metadata("INAM"));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn copy_metadata_from(&mut self, src: &Format) -> Result<(), anyhow::Error> {
+        self.sample_loops.clone_from(&src.sample_loops);
+        self.sampler.clone_from(&src.sampler);
+        self.broadcast_info.clone_from(&src.broadcast_info);
+        self.cue_points.clone_from(&src.cue_points);
+
+        let is_metadata =
+            |chunk: &Chunk| matches!(&chunk.tag, b"LIST" | b"bext" | b"cue " | b"smpl");
+        for (chunks, src_chunks) in [
+            (&mut self.leading_chunks, &src.leading_chunks),
+            (&mut self.trailing_chunks, &src.trailing_chunks),
+        ] {
+            chunks.retain(|chunk| !is_metadata(chunk));
+            chunks.extend(
+                src_chunks
+                    .iter()
+                    .filter(|chunk| is_metadata(chunk))
+                    .cloned(),
+            );
+        }
+        self.sync_total_size()
+    }
+
+    /// `is_standard_rate` checks if the sampling rate is one of [`STANDARD_SAMPLING_RATES`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     assert!(Format::encode(vec![0u8; 16], 1, 44_100, 16)?.is_standard_rate());
+    ///     assert!(!Format::encode(vec![0u8; 16], 1, 44_101, 16)?.is_standard_rate());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn is_standard_rate(&self) -> bool {
+        STANDARD_SAMPLING_RATES.contains(&u32::from_le_bytes(self.sampling_rate))
+    }
+
+    /// `nearest_standard_rate` returns the standard sampling rate closest to this file's.
+    ///
+    /// Ties resolve to the lower rate. See [`STANDARD_SAMPLING_RATES`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 16], 1, 44_101, 16)?;
+    ///     assert_eq!(encoding.nearest_standard_rate(), 44_100);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn nearest_standard_rate(&self) -> u32 {
+        let sampling_rate = u32::from_le_bytes(self.sampling_rate);
+        STANDARD_SAMPLING_RATES
+            .into_iter()
+            .min_by_key(|rate| rate.abs_diff(sampling_rate))
+            .unwrap_or(sampling_rate)
+    }
+
+    /// `duration_to_frames` converts a duration to a number of frames at the sampling rate.
+    ///
+    /// Integer arithmetic on nanoseconds keeps the result exact, rounding down to a whole frame
+    /// and saturating at `u64::MAX`.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Duration to convert.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     assert_eq!(encoding.duration_to_frames(Duration::from_millis(1_500)), 24_000);
+    ///     assert_eq!(encoding.duration_to_frames(Duration::from_nanos(62_499)), 0);
+    ///     assert_eq!(encoding.duration_to_frames(Duration::MAX), u64::MAX);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn duration_to_frames(&self, duration: core::time::Duration) -> u64 {
+        duration_to_frames(duration, u32::from_le_bytes(self.sampling_rate))
+    }
+
+    /// `relabel_sampling_rate` changes the sampling rate of the WAVE PCM file without resampling.
+    ///
+    /// Only the `sampling_rate` and `byte_rate` fields are rewritten, the audio data is left
+    /// untouched. This changes the playback speed and pitch, use it to fix a wrong header.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - New sampling rate.
+    ///
+    /// # Errors
+    ///
+    /// If the byte rate of `rate` cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 16], 2, 44_100, 16)?;
+    ///     let relabeled = encoding.relabel_sampling_rate(48_000)?;
+    ///
+    ///     assert_eq!(relabeled.data, encoding.data);
+    ///     assert_eq!(u32::from_le_bytes(relabeled.sampling_rate), 48_000);
+    ///     assert_eq!(u32::from_le_bytes(relabeled.byte_rate), 48_000 * 2 * 16 / 8);
+    ///
+    ///     let fast = encoding.relabel_sampling_rate(200_000_000)?;
+    ///     assert_eq!(u32::from_le_bytes(fast.byte_rate), 800_000_000);
+    ///     assert!(encoding.relabel_sampling_rate(2_000_000_000).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn relabel_sampling_rate(&self, rate: u32) -> Result<Format, anyhow::Error> {
+        let num_channels = u64::from(u16::from_le_bytes(self.num_channels));
+        let bits_per_sample = u64::from(u16::from_le_bytes(self.bits_per_sample));
+        let byte_rate: u32 = (u64::from(rate) * num_channels * bits_per_sample / 8).try_into()?;
+
+        Ok(Format {
+            sampling_rate: rate.to_le_bytes(),
+            byte_rate: byte_rate.to_le_bytes(),
+            ..self.clone()
+        })
+    }
+
+    // Ensure the audio data is 16 bits per sample.
+    //
+    // # Errors
+    //
+    // If the audio data is not 16 bits per sample.
+    fn require_16_bits(&self) -> Result<(), anyhow::Error> {
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        if bits_per_sample != 16 {
+            return Err(anyhow::anyhow!(
+                "Operation requires 16 bits per sample, got {bits_per_sample} instead."
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Check the size and format code of the format chunk.
+    //
+    // # Errors
+    //
+    // If the format chunk is neither a 16 or 18-byte chunk of a supported format nor a 40-byte
+    // extensible chunk with a supported sub-format.
+    fn check_fmt_chunk(&self) -> Result<(), anyhow::Error> {
+        let fmt_chunk_size_val = u32::from_le_bytes(self.fmt_chunk_size);
+        let fmt_code_val = u16::from_le_bytes(self.fmt_code);
+        let extensible = fmt_code_val == 0xFFFE;
+        let cb_size = self
+            .fmt_extension
+            .get(..2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]));
+        let fmt_chunk_size_ok = u64::from(fmt_chunk_size_val)
+            == 16 + self.fmt_extension.len() as u64
+            && match (fmt_chunk_size_val, cb_size) {
+                (16, None) | (18, Some(0)) => !extensible,
+                (40, Some(22)) => extensible,
+                _ => false,
+            };
+        if !fmt_chunk_size_ok {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires number 16, 18 with cbSize 0, or 40 with cbSize 22 for format code 65534 as bytes 17 - 20, got {fmt_chunk_size_val} instead."
+            ));
+        }
+
+        if !matches!(self.sample_fmt_code(), 1 | 3 | 6 | 7) {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires number 1, 3, 6 or 7, or 65534 with one of them as the sub-format, as bytes 21 - 22, got {fmt_code_val} instead."
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Ensure samples occupy a whole number of bytes.
+    //
+    // # Errors
+    //
+    // If bits per sample is not a multiple of 8.
+    fn require_byte_aligned(&self) -> Result<(), anyhow::Error> {
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        if !bits_per_sample.is_multiple_of(8) {
+            return Err(anyhow::anyhow!(
+                "Unsupported non-byte-aligned bit depth, got {bits_per_sample} bits per sample instead."
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Return the audio data as 16-bit samples.
+    //
+    // # Errors
+    //
+    // If the audio data is not 16 bits per sample.
+    #[cfg(feature = "std")]
+    fn samples_i16(&self) -> Result<Vec<i16>, anyhow::Error> {
+        self.require_16_bits()?;
+        Ok(bytes_to_i16(&self.data))
+    }
+
+    // Encode 16-bit samples with the number of channels, sampling rate and metadata of this file.
+    //
+    // # Arguments
+    //
+    // * `samples` - Interleaved audio samples.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    #[cfg(feature = "std")]
+    fn with_samples_i16(&self, samples: &[i16]) -> Result<Format, anyhow::Error> {
+        let mut format = Format::encode(
+            i16_to_bytes(samples),
+            u16::from_le_bytes(self.num_channels),
+            u32::from_le_bytes(self.sampling_rate),
+            16,
+        )?;
+        format.copy_metadata_from(self)?;
+        Ok(format)
+    }
+
+    // Apply a lowpass or highpass biquad filter to every channel.
+    //
+    // # Arguments
+    //
+    // * `cutoff_hz` - Cutoff frequency.
+    // * `highpass` - Whether to apply a highpass instead of a lowpass filter.
+    //
+    // # Errors
+    //
+    // If the cutoff frequency is not positive or the audio data is not 16 bits per sample.
+    #[cfg(feature = "std")]
+    fn filter(&self, cutoff_hz: f64, highpass: bool) -> Result<Format, anyhow::Error> {
+        if !(cutoff_hz.is_finite() && cutoff_hz > 0.0) {
+            return Err(anyhow::anyhow!(
+                "Cutoff frequency must be a positive number, got {cutoff_hz} instead."
+            ));
+        }
+
+        let samples = self.samples_i16()?;
+        let num_channels = u16::from_le_bytes(self.num_channels).max(1);
+        let biquad = BiquadCoeffs::new(cutoff_hz, u32::from_le_bytes(self.sampling_rate), highpass);
+
+        self.with_samples_i16(&biquad.apply(&samples, usize::from(num_channels)))
+    }
+
+    /// `apply_biquads` runs a cascade of biquad sections over every channel.
+    ///
+    /// Sections are applied in order, each keeping its own filter state per channel. Output
+    /// samples are rounded and saturated to 16 bits after the last section.
+    ///
+    /// # Arguments
+    ///
+    /// * `coeffs` - Biquad sections of the cascade.
+    ///
+    /// # Errors
+    ///
+    /// If a coefficient is not finite or the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{BiquadCoeffs, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data: Vec<u8> = [1_000_i16, -500, 32_767, -32_768].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let passthrough = BiquadCoeffs { b0: 1.0, b1: 0.0, b2: 0.0, a1: 0.0, a2: 0.0 };
+    ///     assert_eq!(encoding.apply_biquads(&[passthrough; 3])?.data, encoding.data);
+    ///     assert_eq!(encoding.apply_biquads(&[])?.data, encoding.data);
+    ///
+    ///     let delay = BiquadCoeffs { b0: 0.0, b1: 1.0, ..passthrough };
+    ///     let delayed = encoding.apply_biquads(&[delay])?;
+    ///     assert_eq!(delayed.data[..4], [0; 4]);
+    ///     assert_eq!(delayed.data[4..], encoding.data[..4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn apply_biquads(&self, coeffs: &[BiquadCoeffs]) -> Result<Format, anyhow::Error> {
+        if let Some(section) = coeffs.iter().find(|section| {
+            ![section.b0, section.b1, section.b2, section.a1, section.a2]
+                .iter()
+                .all(|coeff| coeff.is_finite())
+        }) {
+            return Err(anyhow::anyhow!(
+                "Biquad coefficients must be finite, got {section:?} instead."
+            ));
+        }
+
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let samples = self.samples_i16()?;
+        let samples = coeffs.iter().fold(
+            samples
+                .iter()
+                .map(|&sample| f64::from(sample))
+                .collect::<Vec<_>>(),
+            |samples, section| section.apply_f64(&samples, num_channels),
+        );
+
+        let samples: Vec<i16> = samples.into_iter().map(saturate_i16).collect();
+        self.with_samples_i16(&samples)
+    }
+
+    /// `lowpass` applies a second-order lowpass filter to every channel.
+    ///
+    /// Cutoff frequencies at or above the Nyquist frequency are clamped just below it.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutoff_hz` - Cutoff frequency.
+    ///
+    /// # Errors
+    ///
+    /// If the cutoff frequency is not positive or the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let tone: Vec<u8> = (0..44_100)
+    ///         .map(|n| (f64::from(n) * 5_000.0 * std::f64::consts::TAU / 44_100.0).sin())
+    ///         .flat_map(|x| ((x * 16_000.0) as i16).to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode(tone, 1, 44_100, 16)?;
+    ///     let filtered = encoding.lowpass(200.0)?;
+    ///
+    ///     let peak = |format: &Format| {
+    ///         format.data[4_000..]
+    ///             .chunks_exact(2)
+    ///             .map(|pair| i16::from_le_bytes([pair[0], pair[1]]).unsigned_abs())
+    ///             .max()
+    ///     };
+    ///     assert!(peak(&filtered) < peak(&encoding).map(|peak| peak / 100));
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn lowpass(&self, cutoff_hz: f64) -> Result<Format, anyhow::Error> {
+        self.filter(cutoff_hz, false)
+    }
+
+    /// `highpass` applies a second-order highpass filter to every channel.
+    ///
+    /// Cutoff frequencies at or above the Nyquist frequency are clamped just below it.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutoff_hz` - Cutoff frequency.
+    ///
+    /// # Errors
+    ///
+    /// If the cutoff frequency is not positive or the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let dc: Vec<u8> = [1_000_i16; 4_410].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(dc, 1, 44_100, 16)?;
+    ///     let filtered = encoding.highpass(1_000.0)?;
+    ///
+    ///     let last = &filtered.data[filtered.data.len() - 2..];
+    ///     assert_eq!(i16::from_le_bytes([last[0], last[1]]), 0);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn highpass(&self, cutoff_hz: f64) -> Result<Format, anyhow::Error> {
+        self.filter(cutoff_hz, true)
+    }
+
+    /// `stereo_width` scales the side signal of a stereo file.
+    ///
+    /// The channels are converted to mid/side, the side signal is multiplied by `width` and the
+    /// result is converted back with saturation. A width of 0 yields mono, 1 leaves the audio
+    /// unchanged and anything above 1 widens the stereo image.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Side signal gain.
+    ///
+    /// # Errors
+    ///
+    /// If the file is not stereo, the width is negative or the audio data is not 16 bits per
+    /// sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data: Vec<u8> = [1_000_i16, -500, 200, 300].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 2, 44_100, 16)?;
+    ///
+    ///     assert_eq!(encoding.stereo_width(1.0)?.data, encoding.data);
+    ///
+    ///     let mono = encoding.stereo_width(0.0)?;
+    ///     for frame in mono.data.chunks_exact(4) {
+    ///         assert_eq!(frame[..2], frame[2..]);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn stereo_width(&self, width: f32) -> Result<Format, anyhow::Error> {
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        if num_channels != 2 {
+            return Err(anyhow::anyhow!(
+                "Stereo width requires 2 channels, got {num_channels} instead."
+            ));
+        }
+
+        if !(width.is_finite() && width >= 0.0) {
+            return Err(anyhow::anyhow!(
+                "Stereo width must be a non-negative number, got {width} instead."
+            ));
+        }
+
+        let width = f64::from(width);
+        let samples: Vec<i16> = self
+            .samples_i16()?
+            .chunks_exact(2)
+            .flat_map(|frame| {
+                let (left, right) = (f64::from(frame[0]), f64::from(frame[1]));
+                let mid = f64::midpoint(left, right);
+                let side = (left - right) / 2.0 * width;
+                [saturate_i16(mid + side), saturate_i16(mid - side)]
+            })
+            .collect();
+
+        self.with_samples_i16(&samples)
+    }
+
+    /// `pan` positions mono audio data in a stereo file.
+    ///
+    /// The equal-power panning law keeps the loudness constant across positions: the center
+    /// sends the source to both channels at -3 dB. Samples are saturated to 16 bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Position from -1 (full left) over 0 (center) to 1 (full right).
+    ///
+    /// # Errors
+    ///
+    /// If the file is not mono, the position is outside [-1, 1], the audio data is not 16 bits
+    /// per sample or the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [10_000_i16, -10_000].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 1, 16_000, 16)?;
+    ///
+    ///     let left = encoding.pan(-1.0)?;
+    ///     assert_eq!(u16::from_le_bytes(left.num_channels), 2);
+    ///     assert_eq!((left.sample_at_i16(0, 0)?, left.sample_at_i16(0, 1)?), (10_000, 0));
+    ///
+    ///     let center = encoding.pan(0.0)?;
+    ///     for channel in 0..2 {
+    ///         let gain = f64::from(center.sample_at_i16(1, channel)?) / -10_000.0;
+    ///         assert!((20.0 * gain.log10() + 3.01).abs() < 0.01);
+    ///     }
+    ///
+    ///     assert!(encoding.pan(1.5).is_err());
+    ///     assert!(center.pan(0.0).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn pan(&self, position: f32) -> Result<Format, anyhow::Error> {
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        if num_channels != 1 {
+            return Err(anyhow::anyhow!(
+                "Panning requires 1 channel, got {num_channels} instead."
+            ));
+        }
+
+        if !(-1.0..=1.0).contains(&position) {
+            return Err(anyhow::anyhow!(
+                "Pan position must be between -1 and 1, got {position} instead."
+            ));
+        }
+
+        let angle = (f64::from(position) + 1.0) * std::f64::consts::FRAC_PI_4;
+        let (left, right) = (angle.cos(), angle.sin());
+        let samples: Vec<i16> = self
+            .samples_i16()?
+            .iter()
+            .flat_map(|&sample| {
+                let sample = f64::from(sample);
+                [saturate_i16(sample * left), saturate_i16(sample * right)]
+            })
+            .collect();
+
+        let mut format = Format::encode(
+            i16_to_bytes(&samples),
+            2,
+            u32::from_le_bytes(self.sampling_rate),
+            16,
+        )?;
+        format.copy_metadata_from(self)?;
+        Ok(format)
+    }
+
+    /// `channels` returns the channel layout of the audio data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Channels, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     assert_eq!(Format::encode(vec![0u8; 2], 1, 16_000, 16)?.channels(), Channels::Mono);
+    ///     assert_eq!(Format::encode(vec![0u8; 4], 2, 16_000, 16)?.channels(), Channels::Stereo);
+    ///     assert_eq!(Format::encode(vec![0u8; 12], 6, 16_000, 16)?.channels(), Channels::Multi(6));
+    ///     assert_eq!(u16::from(Channels::Multi(6)), 6);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn channels(&self) -> Channels {
+        Channels::from(u16::from_le_bytes(self.num_channels))
+    }
+
+    /// `split_stereo` splits stereo audio data into left and right mono files.
+    ///
+    /// Both files keep the sample format and metadata of this file. The channel mask of an
+    /// extensible format chunk is narrowed to the speaker of each channel, or cleared if the mask
+    /// does not name one.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data does not have exactly 2 channels or the value cannot fit when
+    /// performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = interleave_i16(&[&[1, 2, 3], &[4, 5, 6]])?;
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let (left, right) = encoding.split_stereo()?;
+    ///     for mono in [&left, &right] {
+    ///         mono.check()?;
+    ///         assert_eq!(u16::from_le_bytes(mono.num_channels), 1);
+    ///         assert_eq!(mono.frame_count(), encoding.frame_count());
+    ///     }
+    ///     assert_eq!(left.data, interleave_i16(&[&[1, 2, 3]])?);
+    ///     assert_eq!(right.data, interleave_i16(&[&[4, 5, 6]])?);
+    ///
+    ///     // Front left and front right speakers of an extensible format chunk.
+    ///     let mut encoding = Format::encode_sample_format(vec![0u8; 12], 2, 48_000, SampleFormat::I24)?;
+    ///     encoding.fmt_extension[4..8].copy_from_slice(&0x3_u32.to_le_bytes());
+    ///     let (left, right) = encoding.split_stereo()?;
+    ///     left.check()?;
+    ///     assert_eq!(left.fmt_extension[4..8], 0x1_u32.to_le_bytes());
+    ///     assert_eq!(right.fmt_extension[4..8], 0x2_u32.to_le_bytes());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn split_stereo(&self) -> Result<(Format, Format), anyhow::Error> {
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        if num_channels != 2 {
+            return Err(anyhow::anyhow!(
+                "Operation requires 2 channels, got {num_channels} instead."
+            ));
+        }
+        self.require_byte_aligned()?;
+
+        let width = usize::from(u16::from_le_bytes(self.bits_per_sample).div_ceil(8));
+        let frames = self.data.chunks_exact(2 * width);
+        let left = frames
+            .clone()
+            .flat_map(|frame| &frame[..width])
+            .copied()
+            .collect();
+        let right = frames.flat_map(|frame| &frame[width..]).copied().collect();
+
+        let mono = |data: Vec<u8>, channel: usize| -> Result<Format, anyhow::Error> {
+            let mut format = Format {
+                num_channels: 1_u16.to_le_bytes(),
+                byte_rate: (u32::from_le_bytes(self.byte_rate) / 2).to_le_bytes(),
+                block_alignment: (u16::from_le_bytes(self.block_alignment) / 2).to_le_bytes(),
+                data_size: u32::try_from(data.len())?.to_le_bytes(),
+                data,
+                ..self.clone()
+            };
+            if let (0xFFFE, Some(mask)) = (
+                u16::from_le_bytes(self.fmt_code),
+                format.fmt_extension.get_mut(4..8),
+            ) {
+                let speakers = u32::from_le_bytes([mask[0], mask[1], mask[2], mask[3]]);
+                let speaker = (0..32)
+                    .map(|bit| 1_u32 << bit)
+                    .filter(|bit| speakers & bit != 0)
+                    .nth(channel)
+                    .unwrap_or(0);
+                mask.copy_from_slice(&speaker.to_le_bytes());
+            }
+            format.sync_total_size()?;
+            Ok(format)
+        };
+
+        Ok((mono(left, 0)?, mono(right, 1)?))
+    }
+
+    /// `to_mono` downmixes the 16-bit audio data to one channel by averaging the channels.
+    ///
+    /// Averaging never clips, but a sound present in only one of two channels comes out 6 dB
+    /// quieter. See [`Format::to_mono_sum`] for a downmix that keeps such peaks. Metadata is
+    /// kept.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample or the value cannot fit when performing type
+    /// conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = interleave_i16(&[&[100, 0, -30_000], &[300, 1_000, -30_000]])?;
+    ///     let mono = Format::encode(data, 2, 16_000, 16)?.to_mono()?;
+    ///
+    ///     mono.check()?;
+    ///     assert_eq!(u16::from_le_bytes(mono.num_channels), 1);
+    ///     assert_eq!(mono.data, interleave_i16(&[&[200, 500, -30_000]])?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_mono(&self) -> Result<Format, anyhow::Error> {
+        self.downmix_i16(false)
+    }
+
+    /// `to_mono_sum` downmixes the 16-bit audio data to one channel by summing the channels.
+    ///
+    /// Unlike [`Format::to_mono`], which averages and so can lose up to 6 dB of peak level on
+    /// stereo, summing keeps transients at full level. The price is clipping: correlated
+    /// channels add up to twice their level, and sums beyond the 16-bit range are saturated.
+    /// Metadata is kept.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample or the value cannot fit when performing type
+    /// conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let tone = |amplitude: f64| -> Vec<i16> {
+    ///         (0..160)
+    ///             .map(|n| (amplitude * (f64::from(n) * std::f64::consts::TAU / 16.0).sin()) as i16)
+    ///             .collect()
+    ///     };
+    ///     let peak = |mono: Format| {
+    ///         mono.data
+    ///             .chunks_exact(2)
+    ///             .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+    ///             .max()
+    ///     };
+    ///
+    ///     let quiet = tone(12_000.0);
+    ///     let encoding = Format::encode(interleave_i16(&[&quiet, &quiet])?, 2, 16_000, 16)?;
+    ///     assert_eq!(peak(encoding.to_mono()?), Some(12_000));
+    ///     assert_eq!(peak(encoding.to_mono_sum()?), Some(24_000));
+    ///
+    ///     let loud = tone(20_000.0);
+    ///     let encoding = Format::encode(interleave_i16(&[&loud, &loud])?, 2, 16_000, 16)?;
+    ///     assert_eq!(peak(encoding.to_mono()?), Some(20_000));
+    ///     assert_eq!(peak(encoding.to_mono_sum()?), Some(32_767));
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_mono_sum(&self) -> Result<Format, anyhow::Error> {
+        self.downmix_i16(true)
+    }
+
+    // Downmix the 16-bit audio data to one channel.
+    //
+    // # Arguments
+    //
+    // * `sum` - Whether to sum the channels with saturation rather than average them.
+    //
+    // # Errors
+    //
+    // If the audio data is not 16 bits per sample or the value cannot fit when performing type
+    // conversion.
+    #[cfg(feature = "std")]
+    fn downmix_i16(&self, sum: bool) -> Result<Format, anyhow::Error> {
+        let num_channels = u16::from_le_bytes(self.num_channels).max(1);
+        let samples: Vec<i16> = self
+            .samples_i16()?
+            .chunks_exact(usize::from(num_channels))
+            .map(|frame| {
+                let total: f64 = frame.iter().map(|&sample| f64::from(sample)).sum();
+                saturate_i16(if sum {
+                    total
+                } else {
+                    total / f64::from(num_channels)
+                })
+            })
+            .collect();
+
+        let mut format = Format::encode(
+            i16_to_bytes(&samples),
+            1,
+            u32::from_le_bytes(self.sampling_rate),
+            16,
+        )?;
+        format.copy_metadata_from(self)?;
+        Ok(format)
+    }
+
+    /// `join_mono` interleaves two mono files into a stereo file.
+    ///
+    /// Both files must share the sampling rate and sample format. When the frame counts differ,
+    /// the shorter file is padded with silence to the length of the longer one. The stereo file
+    /// takes its format chunk from `left` and carries no other metadata. The channel mask of an
+    /// extensible format chunk combines the speakers of both files, or is cleared if they do not
+    /// name one speaker each in left to right order.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - Mono file for the left channel.
+    /// * `right` - Mono file for the right channel.
+    ///
+    /// # Errors
+    ///
+    /// If either file is not mono, the sampling rates or sample formats differ, or the value
+    /// cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let left = Format::encode(interleave_i16(&[&[1, 2, 3]])?, 1, 16_000, 16)?;
+    ///     let right = Format::encode(interleave_i16(&[&[4, 5]])?, 1, 16_000, 16)?;
+    ///
+    ///     let stereo = Format::join_mono(&left, &right)?;
+    ///     stereo.check()?;
+    ///     assert_eq!(u16::from_le_bytes(stereo.num_channels), 2);
+    ///     assert_eq!(stereo.data, interleave_i16(&[&[1, 2, 3], &[4, 5, 0]])?);
+    ///
+    ///     let other_rate = Format::encode(interleave_i16(&[&[4, 5]])?, 1, 8_000, 16)?;
+    ///     assert!(Format::join_mono(&left, &other_rate).is_err());
+    ///
+    ///     // Front left and front right speakers of an extensible format chunk.
+    ///     let mut encoding = Format::encode_sample_format(vec![0u8; 12], 2, 48_000, SampleFormat::I24)?;
+    ///     encoding.fmt_extension[4..8].copy_from_slice(&0x3_u32.to_le_bytes());
+    ///     let (left, right) = encoding.split_stereo()?;
+    ///     let stereo = Format::join_mono(&left, &right)?;
+    ///     stereo.check()?;
+    ///     assert_eq!(stereo.fmt_extension[4..8], 0x3_u32.to_le_bytes());
+    ///
+    ///     let int = Format::encode_sample_format(vec![0u8; 4], 1, 48_000, SampleFormat::I32)?;
+    ///     let float = Format::encode_sample_format(vec![0u8; 4], 1, 48_000, SampleFormat::F32)?;
+    ///     assert!(Format::join_mono(&int, &float).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn join_mono(left: &Format, right: &Format) -> Result<Format, anyhow::Error> {
+        for mono in [left, right] {
+            let num_channels = u16::from_le_bytes(mono.num_channels);
+            if num_channels != 1 {
+                return Err(anyhow::anyhow!(
+                    "Operation requires 1 channel, got {num_channels} instead."
+                ));
+            }
+            mono.require_byte_aligned()?;
+        }
+
+        let sampling_rate = u32::from_le_bytes(left.sampling_rate);
+        let right_rate = u32::from_le_bytes(right.sampling_rate);
+        if sampling_rate != right_rate {
+            return Err(anyhow::anyhow!(
+                "Operation requires matching sampling rates, got {sampling_rate} and {right_rate} instead."
+            ));
+        }
+
+        let bits_per_sample = u16::from_le_bytes(left.bits_per_sample);
+        let right_bits = u16::from_le_bytes(right.bits_per_sample);
+        let (fmt_code, right_code) = (left.sample_fmt_code(), right.sample_fmt_code());
+        if bits_per_sample != right_bits || fmt_code != right_code {
+            return Err(anyhow::anyhow!(
+                "Operation requires matching sample formats, got {bits_per_sample} bits with format code {fmt_code} and {right_bits} bits with format code {right_code} instead."
+            ));
+        }
+
+        // Unsigned 8-bit samples are silent at 128, all other formats at 0.
+        let width = usize::from(bits_per_sample.div_ceil(8));
+        let silence = vec![if bits_per_sample == 8 { 0x80 } else { 0 }; width];
+        let frames = left.frame_count().max(right.frame_count());
+        let mut left_samples = left.data.chunks_exact(width);
+        let mut right_samples = right.data.chunks_exact(width);
+        let mut data = Vec::with_capacity(usize::try_from(frames)? * 2 * width);
+        for _ in 0..frames {
+            data.extend_from_slice(left_samples.next().unwrap_or(&silence));
+            data.extend_from_slice(right_samples.next().unwrap_or(&silence));
+        }
+
+        let mut format = Format::encode(data, 2, sampling_rate, bits_per_sample)?;
+        format.fmt_chunk_size = left.fmt_chunk_size;
+        format.fmt_code = left.fmt_code;
+        format.fmt_extension.clone_from(&left.fmt_extension);
+        if let Some(mask) = format.fmt_extension.get_mut(4..8) {
+            // Speakers are assigned to channels in bit order, so the left speaker must come first.
+            let speakers = match (left.channel_mask(), right.channel_mask()) {
+                (left, right)
+                    if left.is_power_of_two() && right.is_power_of_two() && left < right =>
+                {
+                    left | right
+                }
+                _ => 0,
+            };
+            mask.copy_from_slice(&speakers.to_le_bytes());
+        }
+        format.sync_total_size()?;
+        Ok(format)
+    }
+
+    // Return the channel mask of the extensible format chunk, 0 for any other format chunk.
+    fn channel_mask(&self) -> u32 {
+        match (
+            u16::from_le_bytes(self.fmt_code),
+            self.fmt_extension.get(4..8),
+        ) {
+            (0xFFFE, Some(mask)) => u32::from_le_bytes([mask[0], mask[1], mask[2], mask[3]]),
+            _ => 0,
+        }
+    }
+
+    /// `map_channels_i16` processes every channel of the 16-bit audio data independently.
+    ///
+    /// The audio data is de-interleaved, `f` is called once per channel with the channel index
+    /// and its samples to mutate in place, and the result is interleaved back into a new file.
+    /// Bytes after the last complete frame are left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Callback receiving the channel index and the samples of that channel.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = interleave_i16(&[&[1, 2, 3], &[4, 5, 6]])?;
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///     let mapped = encoding.map_channels_i16(|channel, samples| {
+    ///         if channel == 0 {
+    ///             samples.fill(0);
+    ///         }
+    ///     })?;
+    ///
+    ///     assert_eq!(mapped.data, interleave_i16(&[&[0, 0, 0], &[4, 5, 6]])?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn map_channels_i16<F>(&self, mut f: F) -> Result<Format, anyhow::Error>
+    where
+        F: FnMut(u16, &mut [i16]),
+    {
+        let mut samples = self.samples_i16()?;
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        let width = usize::from(num_channels.max(1));
+        let frames = samples.len() / width;
+
+        for channel in 0..num_channels {
+            let offset = usize::from(channel);
+            let mut buffer: Vec<i16> = (0..frames)
+                .map(|frame| samples[frame * width + offset])
+                .collect();
+            f(channel, &mut buffer);
+            for (frame, sample) in buffer.into_iter().enumerate() {
+                samples[frame * width + offset] = sample;
+            }
+        }
+
+        let mut data = i16_to_bytes(&samples[..frames * width]);
+        data.extend_from_slice(&self.data[data.len()..]);
+        Ok(Format {
+            data,
+            ..self.clone()
+        })
+    }
+
+    /// `to_bits_per_sample` converts the audio data to another bit depth.
+    ///
+    /// Supported bit depths are 8 (unsigned), 16, 24 and 32 of integer PCM, see
+    /// [`Format::ensure_16bit`] for float data. When reducing the bit depth the samples are
+    /// truncated, optionally after adding `dither` to mask the quantization error.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits_per_sample` - Bits per sample of the converted audio data.
+    /// * `dither` - Dither added before truncation.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not integer PCM, or either bit depth is not supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Dither, Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let samples: Vec<i16> = (0..4_096).map(|n| (n % 512) - 256).collect();
+    ///     let data = samples.iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 1, 16_000, 16)?;
+    ///
+    ///     let truncated = encoding.to_bits_per_sample(8, Dither::None)?;
+    ///     let dithered = encoding.to_bits_per_sample(8, Dither::Triangular { seed: 7 })?;
+    ///     assert_ne!(truncated.data, dithered.data);
+    ///
+    ///     let error: i32 = truncated
+    ///         .data
+    ///         .iter()
+    ///         .zip(&dithered.data)
+    ///         .map(|(&a, &b)| (i32::from(a) - i32::from(b)).abs())
+    ///         .sum();
+    ///     assert!(f64::from(error) / 4_096.0 <= 1.0);
+    ///
+    ///     let data = [0.5_f32, -0.5].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let float = Format::encode_sample_format(data, 1, 16_000, SampleFormat::F32)?;
+    ///     assert!(float.to_bits_per_sample(16, Dither::None).is_err());
+    ///     assert_eq!(float.ensure_16bit()?.data, interleave_i16(&[&[16_384, -16_384]])?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_bits_per_sample(
+        &self,
+        bits_per_sample: u16,
+        dither: Dither,
+    ) -> Result<Format, anyhow::Error> {
+        self.require_byte_aligned()?;
+        let fmt_code = self.sample_fmt_code();
+        if fmt_code != 1 {
+            return Err(anyhow::anyhow!(
+                "Bit depth conversion requires integer PCM (format code 1), got format code {fmt_code} instead."
+            ));
+        }
+        let source_bits = u16::from_le_bytes(self.bits_per_sample);
+        for bits in [source_bits, bits_per_sample] {
+            if ![8, 16, 24, 32].contains(&bits) {
+                return Err(anyhow::anyhow!(
+                    "Bit depth conversion supports 8, 16, 24 or 32 bits per sample, got {bits} instead."
+                ));
+            }
+        }
+
+        let source_width = usize::from(source_bits / 8);
+        let target_width = usize::from(bits_per_sample / 8);
+        let lsb = f64::from(1_u32 << (32 - bits_per_sample));
+        let mut rng = match dither {
+            Dither::Triangular { seed } if bits_per_sample < source_bits => {
+                Some(XorShift::new(seed))
+            }
+            _ => None,
+        };
+
+        let mut data = Vec::with_capacity(self.data.len() / source_width * target_width);
+        for bytes in self.data.chunks_exact(source_width) {
+            let mut sample = sample_to_i32(bytes);
+            if let Some(rng) = rng.as_mut() {
+                let offset = (rng.next_f64() - rng.next_f64()) * lsb;
+                sample = saturate_i32(f64::from(sample) + offset);
+            }
+            i32_to_sample(sample, target_width, &mut data);
+        }
+
+        let mut format = Format::encode(
+            data,
+            u16::from_le_bytes(self.num_channels),
+            u32::from_le_bytes(self.sampling_rate),
+            bits_per_sample,
+        )?;
+        format.copy_metadata_from(self)?;
+        Ok(format)
+    }
+
+    /// `ensure_16bit` returns the audio data as 16-bit integer PCM.
+    ///
+    /// Integer data of 8, 24 or 32 bits goes through [`Format::to_bits_per_sample`] without
+    /// dither and 32-bit float data is scaled and saturated, so the many 16-bit-only methods can
+    /// be chained afterwards. 16-bit integer data is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![255, 0, 128], 1, 8_000, 8)?.ensure_16bit()?;
+    ///
+    ///     assert_eq!(u16::from_le_bytes(encoding.bits_per_sample), 16);
+    ///     let samples: Vec<i16> = encoding
+    ///         .data
+    ///         .chunks_exact(2)
+    ///         .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+    ///         .collect();
+    ///     assert_eq!(samples, vec![32_512, -32_768, 0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn ensure_16bit(&self) -> Result<Format, anyhow::Error> {
+        let fmt_code = self.sample_fmt_code();
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        match (fmt_code, bits_per_sample) {
+            (1, 16) => Ok(self.clone()),
+            (1, 8 | 24 | 32) => self.to_bits_per_sample(16, Dither::None),
+            (3, 32) => {
+                let samples: Vec<i16> = self
+                    .normalized_f32()?
+                    .iter()
+                    .map(|&sample| saturate_i16(f64::from(sample) * 32_768.0))
+                    .collect();
+                let mut format = Format::encode(
+                    i16_to_bytes(&samples),
+                    u16::from_le_bytes(self.num_channels),
+                    u32::from_le_bytes(self.sampling_rate),
+                    16,
+                )?;
+                format.copy_metadata_from(self)?;
+                Ok(format)
+            }
+            _ => Err(anyhow::anyhow!(
+                "Operation requires 8, 16, 24 or 32-bit integer PCM or 32-bit float PCM, got format code {fmt_code} with {bits_per_sample} bits per sample instead."
+            )),
+        }
+    }
+
+    /// `as_player_params` returns the stream configuration needed to play the audio data.
+    ///
+    /// The values map directly onto the stream configuration of output libraries such as
+    /// `cpal` or `rodio`, without depending on them.
+    ///
+    /// # Errors
+    ///
+    /// If the format code and bits per sample do not match a [`SampleFormat`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, PlayerParams, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let bytes = Format::encode(vec![0u8; 16], 2, 44_100, 16)?.to_bytes()?;
+    ///     let decoding = Format::decode_bytes(&bytes)?;
+    ///
+    ///     let params = decoding.as_player_params()?;
+    ///     assert_eq!(
+    ///         params,
+    ///         PlayerParams {
+    ///             sampling_rate: 44_100,
+    ///             num_channels: 2,
+    ///             sample_format: SampleFormat::I16,
+    ///         }
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn as_player_params(&self) -> Result<PlayerParams, anyhow::Error> {
+        Ok(PlayerParams {
+            sampling_rate: u32::from_le_bytes(self.sampling_rate),
+            num_channels: u16::from_le_bytes(self.num_channels),
+            sample_format: self.sample_format()?,
+        })
+    }
+
+    /// `valid_bits_per_sample` returns the number of meaningful bits in every sample.
+    ///
+    /// The extensible format can store fewer valid bits than the sample container holds, e.g.
+    /// 24 valid bits in 32-bit containers. Samples are still read and scaled by the container
+    /// size given by `bits_per_sample`, with the unused low bits expected to be zero. Without an
+    /// extensible format chunk this is `bits_per_sample`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = (0x1234_5600_i32).to_le_bytes().to_vec();
+    ///     let mut encoding = Format::encode_sample_format(data, 1, 48_000, SampleFormat::I32)?;
+    ///     encoding.fmt_extension[2..4].copy_from_slice(&24_u16.to_le_bytes());
+    ///
+    ///     assert_eq!(encoding.valid_bits_per_sample(), 24);
+    ///     assert_eq!(u16::from_le_bytes(encoding.bits_per_sample), 32);
+    ///     assert_eq!(encoding.to_i32_samples()?, vec![0x1234_5600]);
+    ///
+    ///     let encoding = Format::encode(vec![0u8; 6], 1, 48_000, 24)?;
+    ///     assert_eq!(encoding.valid_bits_per_sample(), 24);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn valid_bits_per_sample(&self) -> u16 {
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        match (
+            u16::from_le_bytes(self.fmt_code),
+            self.fmt_extension.get(2..4),
+        ) {
+            (0xFFFE, Some(&[b0, b1])) if u16::from_le_bytes([b0, b1]) != 0 => {
+                u16::from_le_bytes([b0, b1])
+            }
+            _ => bits_per_sample,
+        }
+    }
+
+    /// `frame_count` returns the number of complete frames in the audio data.
+    ///
+    /// A frame holds one sample for every channel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 16], 2, 16_000, 16)?;
+    ///     assert_eq!(encoding.frame_count(), 4);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn frame_count(&self) -> u64 {
+        self.frames_in(&self.data)
+    }
+
+    // Count the complete frames of audio data in the format of this file.
+    //
+    // # Arguments
+    //
+    // * `data` - Raw audio data.
+    fn frames_in(&self, data: &[u8]) -> u64 {
+        let num_channels = u64::from(u16::from_le_bytes(self.num_channels));
+        let bytes_per_sample = u64::from(u16::from_le_bytes(self.bits_per_sample).div_ceil(8));
+        let frame_size = num_channels * bytes_per_sample;
+        if frame_size == 0 {
+            return 0;
+        }
+
+        data.len() as u64 / frame_size
+    }
+
+    /// `windows_i16` splits the 16-bit audio data into overlapping analysis windows.
+    ///
+    /// Every window holds `size` interleaved frames, and consecutive windows start `hop` frames
+    /// apart. Windows are produced until one reaches the last frame; that final window is
+    /// padded with zeros to the full size.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Number of frames in a window.
+    /// * `hop` - Number of frames between the starts of consecutive windows.
+    ///
+    /// # Errors
+    ///
+    /// If `size` or `hop` is zero or the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 8_000], 1, 16_000, 16)?;
+    ///     let windows: Vec<Vec<i16>> = encoding.windows_i16(1_024, 512)?.collect();
+    ///
+    ///     // 4000 frames: windows start at 0, 512, ..., 3072, the last one is padded.
+    ///     assert_eq!(windows.len(), 7);
+    ///     assert!(windows.iter().all(|window| window.len() == 1_024));
+    ///     assert_eq!(windows[6][927], 257);
+    ///     assert_eq!(windows[6][928], 0);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn windows_i16(
+        &self,
+        size: usize,
+        hop: usize,
+    ) -> Result<impl Iterator<Item = Vec<i16>>, anyhow::Error> {
+        if size == 0 || hop == 0 {
+            return Err(anyhow::anyhow!(
+                "Window size and hop must be positive, got {size} and {hop} instead."
+            ));
+        }
+
+        let samples = self.samples_i16()?;
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let frames = samples.len() / num_channels;
+        let count = match frames {
+            0 => 0,
+            _ if frames <= size => 1,
+            _ => (frames - size).div_ceil(hop) + 1,
+        };
+
+        Ok((0..count).map(move |idx| {
+            let start = idx * hop * num_channels;
+            let end = (start + size * num_channels).min(frames * num_channels);
+            let mut window = samples[start..end].to_vec();
+            window.resize(size * num_channels, 0);
+            window
+        }))
+    }
+
+    /// `data_chunks` iterates over the audio data in frame-aligned packets.
+    ///
+    /// Every packet holds at most `size` bytes, rounded down to a whole number of frames so a
+    /// frame is never split across packets. A `size` smaller than one frame still yields one
+    /// frame per packet. Only the last packet may be shorter, including any bytes after the
+    /// last complete frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Maximum number of bytes in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data: Vec<u8> = (0..=99).collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let packets: Vec<&[u8]> = encoding.data_chunks(30).collect();
+    ///     assert_eq!(packets.len(), 4);
+    ///     assert!(packets[..3].iter().all(|packet| packet.len() == 28));
+    ///     assert_eq!(packets.concat(), encoding.data);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn data_chunks(&self, size: usize) -> impl Iterator<Item = &[u8]> {
+        let block_alignment = usize::from(u16::from_le_bytes(self.block_alignment).max(1));
+        let packet = (size / block_alignment).max(1) * block_alignment;
+        self.data.chunks(packet)
+    }
+
+    /// `trim_frames` extracts a range of frames by index.
+    ///
+    /// Unlike trimming by duration, no rounding is involved: the audio data is sliced at exact
+    /// frame boundaries. `count` is clamped to the frames available after `start_frame`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_frame` - Index of the first frame to keep.
+    /// * `count` - Number of frames to keep.
+    ///
+    /// # Errors
+    ///
+    /// If `start_frame` is past the last frame or the value cannot fit when performing type
+    /// conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = (0..100_i16).flat_map(|sample| sample.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let trimmed = encoding.trim_frames(10, 10)?;
+    ///     trimmed.check()?;
+    ///     assert_eq!(trimmed.frame_count(), 10);
+    ///     assert_eq!(trimmed.sample_at_i16(0, 0)?, 20);
+    ///     assert_eq!(trimmed.sample_at_i16(9, 1)?, 39);
+    ///
+    ///     assert_eq!(encoding.trim_frames(45, 10)?.frame_count(), 5);
+    ///     assert!(encoding.trim_frames(50, 1).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn trim_frames(&self, start_frame: u64, count: u64) -> Result<Format, anyhow::Error> {
+        let frame_count = self.frame_count();
+        if start_frame >= frame_count {
+            return Err(anyhow::anyhow!(
+                "Start frame must be less than {frame_count}, got {start_frame} instead."
+            ));
+        }
+
+        let frame_size = u64::from(u16::from_le_bytes(self.num_channels))
+            * u64::from(u16::from_le_bytes(self.bits_per_sample).div_ceil(8));
+        let count = count.min(frame_count - start_frame);
+        let start: usize = (start_frame * frame_size).try_into()?;
+        let end: usize = ((start_frame + count) * frame_size).try_into()?;
+        self.with_data(self.data[start..end].to_vec())
+    }
+
+    /// `insert_silence` splices silent frames into the audio data.
+    ///
+    /// Both durations are converted to whole frames as in [`Format::duration_to_frames`], and the
+    /// frames from `at` onwards are shifted later by the inserted silence. Inserting at the end of
+    /// the file appends the silence.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` - Offset at which to insert the silence.
+    /// * `duration` - Length of the silence.
+    ///
+    /// # Errors
+    ///
+    /// If `at` is past the end of the file, the audio data is not a whole number of frames, or
+    /// the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = (1..=400_i16).flat_map(|sample| sample.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 1, 100, 16)?;
+    ///
+    ///     let spliced = encoding.insert_silence(Duration::from_secs(2), Duration::from_secs(1))?;
+    ///     spliced.check()?;
+    ///     assert_eq!(spliced.frame_count(), 500);
+    ///     assert_eq!(spliced.data[..400], encoding.data[..400]);
+    ///     assert!(spliced.data[400..600].iter().all(|&byte| byte == 0));
+    ///     assert_eq!(spliced.data[600..], encoding.data[400..]);
+    ///     assert_eq!(spliced.sample_at_i16(300, 0)?, 201);
+    ///
+    ///     assert!(encoding
+    ///         .insert_silence(Duration::from_secs(5), Duration::from_secs(1))
+    ///         .is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn insert_silence(
+        &self,
+        at: core::time::Duration,
+        duration: core::time::Duration,
+    ) -> Result<Format, anyhow::Error> {
+        let frame_count = self.frame_count();
+        let at_frame = self.duration_to_frames(at);
+        if at_frame > frame_count {
+            return Err(anyhow::anyhow!(
+                "Insertion point must be at most frame {frame_count}, got {at:?} (frame {at_frame}) instead."
+            ));
+        }
+
+        // Unsigned 8-bit samples are silent at 128, all other formats at 0.
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        let frame_size = usize::from(u16::from_le_bytes(self.num_channels))
+            * usize::from(bits_per_sample.div_ceil(8));
+        let split: usize = (at_frame * u64::try_from(frame_size)?).try_into()?;
+        let silence_len = usize::try_from(self.duration_to_frames(duration))?
+            .checked_mul(frame_size)
+            .ok_or_else(|| anyhow::anyhow!("Silence of {duration:?} does not fit in memory."))?;
+
+        let mut data = Vec::with_capacity(self.data.len() + silence_len);
+        data.extend_from_slice(&self.data[..split]);
+        data.resize(
+            split + silence_len,
+            if bits_per_sample == 8 { 0x80 } else { 0 },
+        );
+        data.extend_from_slice(&self.data[split..]);
+        self.with_data(data)
+    }
+
+    /// `rms_envelope` reduces the audio data to `points` RMS levels for drawing a waveform.
+    ///
+    /// The frames are split into `points` buckets of nearly equal length, and each bucket yields
+    /// the RMS level of its normalized samples over all channels, clamped to 0..1. Buckets left
+    /// empty because the file has fewer frames than `points` read 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Number of levels to return.
+    ///
+    /// # Errors
+    ///
+    /// If `points` is 0, or the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit
+    /// float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let silence = Format::encode(vec![0u8; 4 * 1_000], 2, 16_000, 16)?;
+    ///     let envelope = silence.rms_envelope(64)?;
+    ///     assert_eq!(envelope.len(), 64);
+    ///     assert!(envelope.iter().all(|&level| level == 0.0));
+    ///
+    ///     let data = [0_i16, 0, 16_384, -16_384]
+    ///         .iter()
+    ///         .flat_map(|x| x.to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode(data, 1, 16_000, 16)?;
+    ///     assert_eq!(encoding.rms_envelope(2)?, vec![0.0, 0.5]);
+    ///     assert!(encoding.rms_envelope(0).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn rms_envelope(&self, points: usize) -> Result<Vec<f32>, anyhow::Error> {
+        if points == 0 {
+            return Err(anyhow::anyhow!(
+                "Envelope requires at least one point, got {points} instead."
+            ));
+        }
+
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let samples = self.normalized_f32()?;
+        let frames = samples.len() / num_channels;
+        let bucket_start =
+            |point: usize| (point as u128 * frames as u128 / points as u128) as usize;
+
+        Ok((0..points)
+            .map(|point| {
+                let bucket = &samples
+                    [bucket_start(point) * num_channels..bucket_start(point + 1) * num_channels];
+                if bucket.is_empty() {
+                    return 0.0;
+                }
+
+                let energy: f64 = bucket.iter().map(|&sample| f64::from(sample).powi(2)).sum();
+                (energy / bucket.len() as f64).sqrt().min(1.0) as f32
+            })
+            .collect())
+    }
+
+    /// `loudest_window` returns the most energetic stretch of audio data as a standalone file.
+    ///
+    /// A window of `duration` slides across the audio data one frame at a time, and the window
+    /// with the highest RMS level over all channels is kept, the earliest one on ties. Files
+    /// shorter than `duration` are returned whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Length of the window.
+    ///
+    /// # Errors
+    ///
+    /// If the window is shorter than one frame, the file has no audio data, the audio data is not
+    /// 16 bits per sample or the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let samples: Vec<i16> = (0..16_000)
+    ///         .map(|n| if (7_000..7_800).contains(&n) { 20_000 } else { (n % 100) as i16 })
+    ///         .collect();
+    ///     let data = samples.iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 1, 16_000, 16)?;
+    ///
+    ///     let window = encoding.loudest_window(Duration::from_millis(100))?;
+    ///     assert_eq!(window.frame_count(), 1_600);
+    ///     let burst = 20_000_i16.to_le_bytes();
+    ///     assert_eq!(window.data.chunks_exact(2).filter(|pair| *pair == burst).count(), 800);
+    ///
+    ///     assert_eq!(encoding.loudest_window(Duration::from_secs(5))?.frame_count(), 16_000);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn loudest_window(&self, duration: core::time::Duration) -> Result<Format, anyhow::Error> {
+        let window = self.duration_to_frames(duration);
+        if window == 0 {
+            return Err(anyhow::anyhow!(
+                "Window must span at least one frame, got {duration:?} instead."
+            ));
+        }
+
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let energies: Vec<u128> = self
+            .samples_i16()?
+            .chunks_exact(num_channels)
+            .map(|frame| {
+                frame
+                    .iter()
+                    .map(|&sample| u128::from(sample.unsigned_abs()).pow(2))
+                    .sum()
+            })
+            .collect();
+        let window = usize::try_from(window)?.min(energies.len());
+
+        // The window sum is updated as the window slides rather than recomputed.
+        let mut energy: u128 = energies[..window].iter().sum();
+        let (mut loudest, mut start) = (energy, 0);
+        for (idx, (&entering, &leaving)) in energies[window..].iter().zip(&energies).enumerate() {
+            energy = energy + entering - leaving;
+            if energy > loudest {
+                (loudest, start) = (energy, idx + 1);
+            }
+        }
+
+        self.trim_frames(u64::try_from(start)?, u64::try_from(window)?)
+    }
+
+    /// `extract_loop` returns the audio data of a sampler loop as a standalone file.
+    ///
+    /// Loop points of the `smpl` chunk include their end frame, so the extracted file has
+    /// `end - start + 1` frames. It keeps the metadata of this file, with the loop rebased to
+    /// cover the whole extracted audio data as its only loop.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the loop in [`Format::sample_loops`].
+    ///
+    /// # Errors
+    ///
+    /// If there is no loop at `index`, the loop ends before it starts or past the last frame, or
+    /// the value cannot fit when performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleLoop};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = (0..100_i16).flat_map(|sample| sample.to_le_bytes()).collect();
+    ///     let mut encoding = Format::encode(data, 1, 16_000, 16)?;
+    ///     let sample_loop = SampleLoop::new(20, 59);
+    ///     encoding.set_sample_loops(vec![sample_loop])?;
+    ///
+    ///     let extracted = encoding.extract_loop(0)?;
+    ///     extracted.check()?;
+    ///     assert_eq!(extracted.frame_count(), u64::from(sample_loop.end - sample_loop.start + 1));
+    ///     assert_eq!(extracted.sample_at_i16(0, 0)?, 20);
+    ///     assert_eq!(extracted.sample_at_i16(39, 0)?, 59);
+    ///     assert_eq!((extracted.sample_loops[0].start, extracted.sample_loops[0].end), (0, 39));
+    ///
+    ///     assert!(encoding.extract_loop(1).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn extract_loop(&self, index: usize) -> Result<Format, anyhow::Error> {
+        let Some(&sample_loop) = self.sample_loops.get(index) else {
+            let count = self.sample_loops.len();
+            return Err(anyhow::anyhow!(
+                "Loop index must be less than {count}, got {index} instead."
+            ));
+        };
+
+        let frame_count = self.frame_count();
+        let SampleLoop { start, end, .. } = sample_loop;
+        if end < start || u64::from(end) >= frame_count {
+            return Err(anyhow::anyhow!(
+                "Loop must end at or after its start and before frame {frame_count}, got {start} - {end} instead."
+            ));
+        }
+
+        let mut format = self.trim_frames(u64::from(start), u64::from(end - start) + 1)?;
+        format.set_sample_loops(vec![SampleLoop {
+            start: 0,
+            end: end - start,
+            ..sample_loop
+        }])?;
+        Ok(format)
+    }
+
+    /// `reserve_frames` reserves capacity for at least `frames` more frames of audio data.
+    ///
+    /// The frame size is taken from `block_alignment`. Neither the audio data nor any header
+    /// field changes, this only avoids reallocations while a large file is built up.
+    ///
+    /// # Arguments
+    ///
+    /// * `frames` - Number of additional frames.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows or cannot be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![1u8; 16], 2, 16_000, 16)?;
+    ///     let before = encoding.clone();
+    ///
+    ///     encoding.reserve_frames(16_000)?;
+    ///     assert!(encoding.data.capacity() >= 16 + 4 * 16_000);
+    ///     assert_eq!(encoding.data, before.data);
+    ///     assert_eq!(encoding.data_size, before.data_size);
+    ///     assert!(encoding == before);
+    ///
+    ///     assert!(encoding.reserve_frames(u64::MAX).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn reserve_frames(&mut self, frames: u64) -> Result<(), anyhow::Error> {
+        let block_alignment = u64::from(u16::from_le_bytes(self.block_alignment));
+        let Some(nbytes) = frames
+            .checked_mul(block_alignment)
+            .and_then(|nbytes| usize::try_from(nbytes).ok())
+        else {
+            return Err(anyhow::anyhow!(
+                "Reserving {frames} frames of {block_alignment} bytes exceeds addressable memory on this platform."
+            ));
+        };
+
+        self.data
+            .try_reserve(nbytes)
+            .map_err(|err| anyhow::anyhow!("Failed reserving {nbytes} bytes: {err}."))
+    }
+
+    /// `with_data` returns a copy of this file holding other audio data.
+    ///
+    /// The format and metadata are kept, while `data_size` and `total_size` are recomputed for
+    /// the new audio data.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Audio data in the sample format of this file.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data does not hold a whole number of frames or the value cannot fit when
+    /// performing type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 16], 2, 16_000, 16)?;
+    ///
+    ///     let longer = encoding.with_data(vec![1u8; 64])?;
+    ///     longer.check()?;
+    ///     assert_eq!(longer.data, vec![1u8; 64]);
+    ///     assert_eq!(u32::from_le_bytes(longer.data_size), 64);
+    ///     assert_eq!(u32::from_le_bytes(longer.total_size), 100);
+    ///     assert_eq!(longer.frame_count(), 16);
+    ///
+    ///     assert!(encoding.with_data(vec![1u8; 6]).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_data(&self, data: Vec<u8>) -> Result<Format, anyhow::Error> {
+        let frame_size = usize::from(u16::from_le_bytes(self.num_channels))
+            * usize::from(u16::from_le_bytes(self.bits_per_sample).div_ceil(8));
+        if frame_size == 0 || !data.len().is_multiple_of(frame_size) {
+            return Err(anyhow::anyhow!(
+                "Audio data must hold whole frames of {frame_size} bytes, got {} bytes instead.",
+                data.len()
+            ));
+        }
+
+        let mut format = Format {
+            data_size: u32::try_from(data.len())?.to_le_bytes(),
+            data,
+            ..self.clone()
+        };
+        format.sync_total_size()?;
+        Ok(format)
+    }
+
+    /// `sample_at_i16` returns a single 16-bit sample without decoding the rest of the data.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Index of the frame.
+    /// * `channel` - Index of the channel within the frame.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample or either index is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [10_i16, -20, 30, -40].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     assert_eq!(encoding.sample_at_i16(0, 1)?, -20);
+    ///     assert_eq!(encoding.sample_at_i16(1, 0)?, 30);
+    ///     assert!(encoding.sample_at_i16(2, 0).is_err());
+    ///     assert!(encoding.sample_at_i16(0, 2).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sample_at_i16(&self, frame: u64, channel: u16) -> Result<i16, anyhow::Error> {
+        self.sample_at_i16_in(&self.data, frame, channel)
+    }
+
+    // Read a single 16-bit sample of audio data in the format of this file.
+    //
+    // # Arguments
+    //
+    // * `data` - Raw audio data.
+    // * `frame` - Index of the frame.
+    // * `channel` - Index of the channel within the frame.
+    //
+    // # Errors
+    //
+    // See `Format::sample_at_i16`.
+    fn sample_at_i16_in(
+        &self,
+        data: &[u8],
+        frame: u64,
+        channel: u16,
+    ) -> Result<i16, anyhow::Error> {
+        self.require_16_bits()?;
+
+        let frame_count = self.frames_in(data);
+        if frame >= frame_count {
+            return Err(anyhow::anyhow!(
+                "Frame index must be less than {frame_count}, got {frame} instead."
+            ));
+        }
+
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        if channel >= num_channels {
+            return Err(anyhow::anyhow!(
+                "Channel index must be less than {num_channels}, got {channel} instead."
+            ));
+        }
+
+        let offset: usize =
+            ((frame * u64::from(num_channels) + u64::from(channel)) * 2).try_into()?;
+        Ok(i16::from_le_bytes([data[offset], data[offset + 1]]))
+    }
+
+    /// `data_size_matches` returns whether the `data_size` field equals the length of the audio
+    /// data.
+    ///
+    /// Decoding a truncated file keeps the declared size while reading fewer bytes, so check this
+    /// before relying on math derived from `data_size`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let bytes = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+    ///     assert!(Format::decode_bytes(&bytes)?.data_size_matches());
+    ///
+    ///     let truncated = Format::decode_bytes(&bytes[..bytes.len() - 4])?;
+    ///     assert_eq!(truncated.data.len(), 12);
+    ///     assert!(!truncated.data_size_matches());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn data_size_matches(&self) -> bool {
+        usize::try_from(u32::from_le_bytes(self.data_size)) == Ok(self.data.len())
+    }
+
+    /// `total_size_matches` returns whether the `total_size` field agreed with the length of the
+    /// file this was decoded from.
+    ///
+    /// Always true for files not decoded from a path, and once `total_size` is rewritten (e.g.
+    /// by [`Format::set_metadata`]). See [`Format::decode`] for an example.
+    #[must_use]
+    pub fn total_size_matches(&self) -> bool {
+        self.file_size.is_none()
+    }
+
+    /// `consistency_report` lists every derived header field that disagrees with the value
+    /// recomputed from the rest of the file.
+    ///
+    /// The file is not modified, an empty report means the header is consistent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0u8; 16], 2, 16_000, 16)?;
+    ///     assert!(encoding.consistency_report().is_empty());
+    ///
+    ///     encoding.block_alignment = 2_u16.to_le_bytes();
+    ///     let report = encoding.consistency_report();
+    ///     assert!(report.iter().any(|line| line.contains("block alignment")));
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn consistency_report(&self) -> Vec<String> {
+        let num_channels = u64::from(u16::from_le_bytes(self.num_channels));
+        let sampling_rate = u64::from(u32::from_le_bytes(self.sampling_rate));
+        let bits_per_sample = u64::from(u16::from_le_bytes(self.bits_per_sample));
+        let block_alignment = u64::from(u16::from_le_bytes(self.block_alignment));
+        let byte_rate = u64::from(u32::from_le_bytes(self.byte_rate));
+        let data_size = u64::from(u32::from_le_bytes(self.data_size));
+        let total_size = u64::from(u32::from_le_bytes(self.total_size));
+
+        let mut report = Vec::new();
+
+        let expected = num_channels * bits_per_sample.div_ceil(8);
+        if block_alignment != expected {
+            report.push(format!(
+                "Expected block alignment {expected} (num_channels * bits_per_sample / 8), got {block_alignment} instead."
+            ));
+        }
+
+        let expected = sampling_rate * block_alignment;
+        if byte_rate != expected {
+            report.push(format!(
+                "Expected byte rate {expected} (sampling_rate * block_alignment), got {byte_rate} instead."
+            ));
+        }
+
+        let expected = self.data.len() as u64;
+        if data_size != expected {
+            report.push(format!(
+                "Expected data size {expected} (length of the audio data), got {data_size} instead."
+            ));
+        }
+
+        let header_size = self.header().map_or(44, |header| header.len() as u64);
+        let trailer_size = self.trailer().map_or(0, |trailer| trailer.len() as u64);
+        let expected = header_size - 8 + data_size + trailer_size;
+        if total_size != expected {
+            report.push(format!(
+                "Expected total size {expected} (chunks following bytes 1 - 8), got {total_size} instead."
+            ));
+        }
+
+        if let Some(file_size) = self.file_size {
+            let expected = file_size.saturating_sub(8);
+            report.push(format!(
+                "Expected total size {expected} (length of the file minus 8), got {total_size} instead."
+            ));
+        }
+
+        report
+    }
+
+    /// `declick` fades the audio data out and back in around sudden jumps between samples.
+    ///
+    /// A jump is a difference between adjacent samples of a channel larger than `threshold`,
+    /// such as the discontinuity left by trimming or concatenation. The gain of that channel
+    /// ramps linearly down to 0 over half of `window` before the jump and back up over the half
+    /// after it, removing the pop. Samples are saturated to 16 bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Total length of each fade out and fade in.
+    /// * `threshold` - Largest difference between adjacent samples that is not a jump.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample or the value cannot fit when performing type
+    /// conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let samples: Vec<i16> = (0..1_000).map(|n| if n < 500 { 8_000 } else { -8_000 }).collect();
+    ///     let data = samples.iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 1, 16_000, 16)?;
+    ///
+    ///     let declicked = encoding.declick(Duration::from_millis(8), 1_000)?;
+    ///     let jump = |format: &Format| -> Result<i32, anyhow::Error> {
+    ///         Ok((i32::from(format.sample_at_i16(500, 0)?) - i32::from(format.sample_at_i16(499, 0)?)).abs())
+    ///     };
+    ///     assert_eq!(jump(&encoding)?, 16_000);
+    ///     assert!(jump(&declicked)? < 1_000);
+    ///     assert_eq!(declicked.sample_at_i16(0, 0)?, 8_000);
+    ///     assert_eq!(declicked.sample_at_i16(999, 0)?, -8_000);
+    ///
+    ///     assert_eq!(encoding.declick(Duration::from_millis(8), 16_000)?.data, encoding.data);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn declick(
+        &self,
+        window: core::time::Duration,
+        threshold: u16,
+    ) -> Result<Format, anyhow::Error> {
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let half = usize::try_from(self.duration_to_frames(window) / 2)?;
+        let mut samples = self.samples_i16()?;
+        let frame_count = samples.len() / num_channels;
+        if half == 0 {
+            return self.with_samples_i16(&samples);
+        }
+
+        for channel in 0..num_channels {
+            let sample = |frame: usize| i32::from(samples[frame * num_channels + channel]);
+            let jumps: Vec<usize> = (1..frame_count)
+                .filter(|&frame| {
+                    (sample(frame) - sample(frame - 1)).unsigned_abs() > u32::from(threshold)
+                })
+                .collect();
+
+            let mut gains = vec![1.0_f64; frame_count];
+            for &jump in &jumps {
+                let (start, end) = (jump.saturating_sub(half), (jump + half).min(frame_count));
+                for (frame, gain) in (start..end).zip(&mut gains[start..end]) {
+                    let distance = if frame < jump {
+                        jump - 1 - frame
+                    } else {
+                        frame - jump
+                    };
+                    *gain = gain.min(distance as f64 / half as f64);
+                }
+            }
+
+            for (frame, gain) in gains.into_iter().enumerate() {
+                let sample = &mut samples[frame * num_channels + channel];
+                *sample = saturate_i16(f64::from(*sample) * gain);
+            }
+        }
+
+        self.with_samples_i16(&samples)
+    }
+
+    /// `apply_envelope` applies a gain envelope to every channel.
+    ///
+    /// The gain is linearly interpolated between the `(time, gain)` breakpoints and held constant
+    /// before the first and after the last breakpoint. Samples are saturated to 16 bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Breakpoints sorted by time.
+    ///
+    /// # Errors
+    ///
+    /// If there are no breakpoints, they are not sorted by time, a gain is not finite or the audio
+    /// data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [10_000_i16; 100].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 1, 100, 16)?;
+    ///     let faded = encoding.apply_envelope(&[
+    ///         (Duration::ZERO, 1.0),
+    ///         (Duration::from_secs(1), 0.0),
+    ///     ])?;
+    ///
+    ///     let expected: Vec<u8> = (0..100)
+    ///         .map(|n| (10_000.0 * (1.0 - f64::from(n) / 100.0)).round() as i16)
+    ///         .flat_map(|x| x.to_le_bytes())
+    ///         .collect();
+    ///     assert_eq!(faded.data, expected);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn apply_envelope(
+        &self,
+        points: &[(core::time::Duration, f32)],
+    ) -> Result<Format, anyhow::Error> {
+        if points.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Gain envelope requires at least one breakpoint, got 0 instead."
+            ));
+        }
+
+        if let Some(idx) = points.windows(2).position(|pair| pair[1].0 < pair[0].0) {
+            return Err(anyhow::anyhow!(
+                "Gain envelope breakpoints must be sorted by time, breakpoint {} comes before breakpoint {idx}.",
+                idx + 1
+            ));
+        }
+
+        if let Some((_, gain)) = points.iter().find(|(_, gain)| !gain.is_finite()) {
+            return Err(anyhow::anyhow!(
+                "Gain envelope requires finite gains, got {gain} instead."
+            ));
+        }
+
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let breakpoints: Vec<u64> = points
+            .iter()
+            .map(|&(time, _)| self.duration_to_frames(time))
+            .collect();
+        let mut samples = self.samples_i16()?;
+
+        let mut segment = 0;
+        for (frame, chunk) in (0_u64..).zip(samples.chunks_mut(num_channels)) {
+            while segment + 1 < points.len() && breakpoints[segment + 1] <= frame {
+                segment += 1;
+            }
+
+            let start = breakpoints[segment];
+            let start_gain = f64::from(points[segment].1);
+            let gain = match points.get(segment + 1) {
+                Some(&(_, end_gain)) if frame > start => {
+                    let end = breakpoints[segment + 1];
+                    let position = (frame - start) as f64 / (end - start) as f64;
+                    start_gain + (f64::from(end_gain) - start_gain) * position
+                }
+                _ => start_gain,
+            };
+
+            for sample in chunk {
+                *sample = saturate_i16(f64::from(*sample) * gain);
+            }
+        }
+
+        self.with_samples_i16(&samples)
+    }
+
+    /// `to_f32_pcm_bytes` converts the audio data to normalized 32-bit floats.
+    ///
+    /// Samples are scaled to [-1, 1) and returned as interleaved little-endian bytes without a
+    /// header, ready to be fed to machine learning frameworks.
+    ///
+    /// # Arguments
+    ///
+    /// * `sanitize_floats` - Whether to replace NaN float samples with 0.0 and clamp infinite
+    ///   ones to ±1.0, rather than preserving their exact bits.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 24], 2, 16_000, 24)?;
+    ///     let bytes = encoding.to_f32_pcm_bytes(false)?;
+    ///     assert_eq!(bytes.len() as u64, encoding.frame_count() * 2 * 4);
+    ///
+    ///     let data = [f32::from_bits(0x7FC0_1234), f32::NEG_INFINITY]
+    ///         .iter()
+    ///         .flat_map(|sample| sample.to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode_sample_format(data, 1, 16_000, SampleFormat::F32)?;
+    ///     assert_eq!(encoding.to_f32_pcm_bytes(false)?, encoding.data);
+    ///
+    ///     let bytes = encoding.to_f32_pcm_bytes(true)?;
+    ///     assert_eq!(bytes[..4], 0.0_f32.to_le_bytes());
+    ///     assert_eq!(bytes[4..], (-1.0_f32).to_le_bytes());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_f32_pcm_bytes(&self, sanitize_floats: bool) -> Result<Vec<u8>, anyhow::Error> {
+        Ok(self
+            .accessor_f32(sanitize_floats)?
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect())
+    }
+
+    /// `to_i32_samples` widens the integer audio data to 32-bit samples.
+    ///
+    /// Samples are left-justified: they are shifted so their most significant bit lands on bit
+    /// 31, which makes full scale the same at every depth. A 16-bit sample `x` becomes `x << 16`
+    /// and unsigned 8-bit samples are centered on zero before being shifted by 24.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 8, 16, 24 or 32-bit integer PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [i16::MAX, i16::MIN, 1]
+    ///         .iter()
+    ///         .flat_map(|sample| sample.to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode(data, 1, 16_000, 16)?;
+    ///     assert_eq!(
+    ///         encoding.to_i32_samples()?,
+    ///         vec![0x7FFF_0000, i32::MIN, 0x0001_0000]
+    ///     );
+    ///
+    ///     let encoding = Format::encode(vec![255, 0, 128], 1, 16_000, 8)?;
+    ///     assert_eq!(encoding.to_i32_samples()?, vec![0x7F00_0000, i32::MIN, 0]);
+    ///
+    ///     let packed = Format::encode(vec![0u8; 15], 2, 16_000, 20)?;
+    ///     let message = "Unsupported non-byte-aligned bit depth, got 20 bits per sample instead.";
+    ///     assert_eq!(packed.check().err().unwrap().to_string(), message);
+    ///     assert_eq!(packed.to_i32_samples().err().unwrap().to_string(), message);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_i32_samples(&self) -> Result<Vec<i32>, anyhow::Error> {
+        self.require_byte_aligned()?;
+        let fmt_code = self.sample_fmt_code();
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        match (fmt_code, bits_per_sample) {
+            (1, 8 | 16 | 24 | 32) => Ok(self
+                .data
+                .chunks_exact(usize::from(bits_per_sample / 8))
+                .map(sample_to_i32)
+                .collect()),
+            _ => Err(anyhow::anyhow!(
+                "Operation requires 8, 16, 24 or 32-bit integer PCM, got format code {fmt_code} with {bits_per_sample} bits per sample instead."
+            )),
+        }
+    }
+
+    /// `to_mulaw` compresses the 16-bit audio data to raw G.711 mu-law bytes.
+    ///
+    /// One byte is produced per sample, channels stay interleaved and no header is written.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [0_i16, 1_000, -1_000, i16::MAX, i16::MIN]
+    ///         .iter()
+    ///         .flat_map(|sample| sample.to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode(data, 1, 8_000, 16)?;
+    ///     assert_eq!(encoding.to_mulaw()?, vec![0xFF, 0xCE, 0x4E, 0x80, 0x00]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_mulaw(&self) -> Result<Vec<u8>, anyhow::Error> {
+        self.require_16_bits()?;
+        Ok(self
+            .data
+            .chunks_exact(2)
+            .map(|bytes| linear_to_mulaw(i16::from_le_bytes([bytes[0], bytes[1]])))
+            .collect())
+    }
+
+    /// `to_alaw` compresses the 16-bit audio data to raw G.711 A-law bytes.
+    ///
+    /// One byte is produced per sample, channels stay interleaved and no header is written.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [0_i16, 1_000, -1_000, i16::MAX, i16::MIN]
+    ///         .iter()
+    ///         .flat_map(|sample| sample.to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode(data, 1, 8_000, 16)?;
+    ///     assert_eq!(encoding.to_alaw()?, vec![0xD5, 0xFA, 0x7A, 0xAA, 0x2A]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_alaw(&self) -> Result<Vec<u8>, anyhow::Error> {
+        self.require_16_bits()?;
+        Ok(self
+            .data
+            .chunks_exact(2)
+            .map(|bytes| linear_to_alaw(i16::from_le_bytes([bytes[0], bytes[1]])))
+            .collect())
+    }
+
+    /// `companding` returns the G.711 companding of the audio data, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Companding, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0xFF; 8], 1, 8_000, 8)?;
+    ///     assert_eq!(encoding.companding(), None);
+    ///
+    ///     encoding.fmt_code = 7_u16.to_le_bytes();
+    ///     assert_eq!(encoding.companding(), Some(Companding::MuLaw));
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn companding(&self) -> Option<Companding> {
+        match u16::from_le_bytes(self.fmt_code) {
+            6 => Some(Companding::ALaw),
+            7 => Some(Companding::MuLaw),
+            _ => None,
+        }
+    }
+
+    /// `decode_companded` expands A-law or mu-law audio data to 16-bit PCM.
+    ///
+    /// Each 8-bit sample is expanded with the G.711 tables, inverting [`Format::to_alaw`] and
+    /// [`Format::to_mulaw`] up to the quantization error. The original coding is available
+    /// through [`Format::companding`] before expansion.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 8-bit A-law or mu-law or the value cannot fit when performing
+    /// type conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [0_i16, 1_000, -1_000, i16::MAX, i16::MIN]
+    ///         .iter()
+    ///         .flat_map(|sample| sample.to_le_bytes())
+    ///         .collect();
+    ///     let pcm = Format::encode(data, 1, 8_000, 16)?;
+    ///
+    ///     let mut mulaw = Format::encode(pcm.to_mulaw()?, 1, 8_000, 8)?;
+    ///     mulaw.fmt_code = 7_u16.to_le_bytes();
+    ///     mulaw.check()?;
+    ///     let decoding = mulaw.decode_companded()?;
+    ///     decoding.check()?;
+    ///
+    ///     let samples: Vec<i16> = (0..5)
+    ///         .map(|frame| decoding.sample_at_i16(frame, 0))
+    ///         .collect::<Result<_, _>>()?;
+    ///     assert_eq!(samples, vec![0, 988, -988, 32_124, -32_124]);
+    ///     assert_eq!(decoding.to_mulaw()?, pcm.to_mulaw()?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode_companded(&self) -> Result<Format, anyhow::Error> {
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        let expand = match (self.companding(), bits_per_sample) {
+            (Some(Companding::ALaw), 8) => alaw_to_linear,
+            (Some(Companding::MuLaw), 8) => mulaw_to_linear,
+            _ => {
+                let fmt_code = u16::from_le_bytes(self.fmt_code);
+                return Err(anyhow::anyhow!(
+                    "Operation requires 8-bit A-law or mu-law audio data, got format code {fmt_code} with {bits_per_sample} bits per sample instead."
+                ));
+            }
+        };
+
+        let samples: Vec<i16> = self.data.iter().map(|&byte| expand(byte)).collect();
+        let mut format = Format::encode(
+            i16_to_bytes(&samples),
+            u16::from_le_bytes(self.num_channels),
+            u32::from_le_bytes(self.sampling_rate),
+            16,
+        )?;
+        format.copy_metadata_from(self)?;
+        Ok(format)
+    }
+
+    /// `reinterpret_8bit_signed` fixes 8-bit audio data that was stored as signed samples.
+    ///
+    /// WAVE stores 8-bit samples as unsigned values centered on 128, which every method of this
+    /// crate assumes. Some tools write signed 8-bit samples instead; flipping the sign bit of
+    /// every byte turns such data into the canonical unsigned form, offsetting each sample by
+    /// 128. Applying it twice restores the original data.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 8 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let signed = [0_i8, -128, 127, -1].iter().map(|&sample| sample as u8).collect();
+    ///     let encoding = Format::encode(signed, 1, 8_000, 8)?;
+    ///
+    ///     let unsigned = encoding.reinterpret_8bit_signed()?;
+    ///     assert_eq!(unsigned.data, vec![128, 0, 255, 127]);
+    ///     assert_eq!(unsigned.reinterpret_8bit_signed()?.data, encoding.data);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn reinterpret_8bit_signed(&self) -> Result<Format, anyhow::Error> {
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        if bits_per_sample != 8 {
+            return Err(anyhow::anyhow!(
+                "Operation requires 8 bits per sample, got {bits_per_sample} instead."
+            ));
+        }
+
+        Ok(Format {
+            data: self.data.iter().map(|byte| byte ^ 0x80).collect(),
+            ..self.clone()
+        })
+    }
+
+    /// `channels_f32` de-interleaves the audio data into normalized per-channel buffers.
+    ///
+    /// Samples are scaled to [-1, 1) as in [`Format::to_f32_pcm_bytes`], which suits FFT and
+    /// analysis libraries expecting one contiguous buffer per channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `sanitize_floats` - Whether to replace NaN float samples with 0.0 and clamp infinite
+    ///   ones to ±1.0, rather than preserving their exact bits.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM, or if
+    /// it is not a whole number of frames.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [16_384_i16, -16_384, 0, 8_192]
+    ///         .iter()
+    ///         .flat_map(|sample| sample.to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let channels = encoding.channels_f32(false)?;
+    ///     assert_eq!(channels, vec![vec![0.5, 0.0], vec![-0.5, 0.25]]);
+    ///
+    ///     let encoding = Format::encode(vec![0u8; 6], 2, 16_000, 16)?;
+    ///     assert!(encoding.channels_f32(false).is_err());
+    ///
+    ///     let nan = f32::from_bits(0x7FC0_1234);
+    ///     let data = [nan, f32::INFINITY, 0.25, -0.5]
+    ///         .iter()
+    ///         .flat_map(|sample| sample.to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode_sample_format(data, 2, 16_000, SampleFormat::F32)?;
+    ///
+    ///     let channels = encoding.channels_f32(false)?;
+    ///     assert_eq!(channels[0][0].to_bits(), nan.to_bits());
+    ///     assert_eq!(channels[1][0], f32::INFINITY);
+    ///
+    ///     let channels = encoding.channels_f32(true)?;
+    ///     assert_eq!(channels, vec![vec![0.0, 0.25], vec![1.0, -0.5]]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn channels_f32(&self, sanitize_floats: bool) -> Result<Vec<Vec<f32>>, anyhow::Error> {
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels));
+        let block_alignment = usize::from(u16::from_le_bytes(self.block_alignment));
+        if num_channels == 0
+            || block_alignment == 0
+            || !self.data.len().is_multiple_of(block_alignment)
+        {
+            let data_len = self.data.len();
+            return Err(anyhow::anyhow!(
+                "Operation requires a whole number of {block_alignment}-byte frames across {num_channels} channels, got {data_len} bytes instead."
+            ));
+        }
+
+        let samples = self.accessor_f32(sanitize_floats)?;
+        let mut channels = vec![Vec::with_capacity(samples.len() / num_channels); num_channels];
+        for frame in samples.chunks_exact(num_channels) {
+            for (channel, &sample) in channels.iter_mut().zip(frame) {
+                channel.push(sample);
+            }
+        }
+        Ok(channels)
+    }
+
+    /// `deinterleave_into_i16` de-interleaves the 16-bit samples into caller-owned buffers.
+    ///
+    /// Each buffer is cleared and refilled with one channel, reusing its capacity so repeated
+    /// calls in a hot loop need not allocate. A trailing partial frame is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - One buffer per channel.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample, or if `out` does not hold one buffer per
+    /// channel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = (0..8_i16).flat_map(|sample| sample.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let mut out = vec![Vec::new(), Vec::new()];
+    ///     encoding.deinterleave_into_i16(&mut out)?;
+    ///     assert_eq!(out, vec![vec![0, 2, 4, 6], vec![1, 3, 5, 7]]);
+    ///
+    ///     let capacities: Vec<usize> = out.iter().map(Vec::capacity).collect();
+    ///     let data = (10..18_i16).flat_map(|sample| sample.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///     encoding.deinterleave_into_i16(&mut out)?;
+    ///     assert_eq!(out, vec![vec![10, 12, 14, 16], vec![11, 13, 15, 17]]);
+    ///     assert_eq!(out.iter().map(Vec::capacity).collect::<Vec<_>>(), capacities);
+    ///
+    ///     assert!(encoding.deinterleave_into_i16(&mut out[..1]).is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn deinterleave_into_i16(&self, out: &mut [Vec<i16>]) -> Result<(), anyhow::Error> {
+        self.deinterleave_into_i16_in(&self.data, out)
+    }
+
+    // De-interleave 16-bit audio data in the format of this file into caller-owned buffers.
+    //
+    // # Arguments
+    //
+    // * `data` - Raw audio data.
+    // * `out` - One buffer per channel.
+    //
+    // # Errors
+    //
+    // See `Format::deinterleave_into_i16`.
+    fn deinterleave_into_i16_in(
+        &self,
+        data: &[u8],
+        out: &mut [Vec<i16>],
+    ) -> Result<(), anyhow::Error> {
+        self.require_16_bits()?;
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels));
+        if out.len() != num_channels {
+            return Err(anyhow::anyhow!(
+                "Operation requires one buffer per channel across {num_channels} channels, got {} buffers instead.",
+                out.len()
+            ));
+        }
+
+        let frames = data.len() / (2 * num_channels.max(1));
+        for channel in out.iter_mut() {
+            channel.clear();
+            channel.reserve(frames);
+        }
+        for frame in data.chunks_exact(2 * num_channels.max(1)) {
+            for (channel, bytes) in out.iter_mut().zip(frame.chunks_exact(2)) {
+                channel.push(i16::from_le_bytes([bytes[0], bytes[1]]));
+            }
+        }
+        Ok(())
+    }
+
+    /// `channels_mut_i16` de-interleaves the 16-bit samples for editing in place.
+    ///
+    /// The returned [`ChannelsGuard`] holds one buffer per channel and interleaves them back into
+    /// the audio data when committed or dropped. A trailing partial frame is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = interleave_i16(&[&[1, 2, 3], &[4, 5, 6]])?;
+    ///     let mut encoding = Format::encode(data, 2, 16_000, 16)?;
+    ///
+    ///     let mut channels = encoding.channels_mut_i16()?;
+    ///     channels[1].reverse();
+    ///     channels.commit();
+    ///     assert_eq!(encoding.data, interleave_i16(&[&[1, 2, 3], &[6, 5, 4]])?);
+    ///
+    ///     {
+    ///         let mut channels = encoding.channels_mut_i16()?;
+    ///         channels[0].fill(0);
+    ///     }
+    ///     assert_eq!(encoding.data, interleave_i16(&[&[0, 0, 0], &[6, 5, 4]])?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn channels_mut_i16(&mut self) -> Result<ChannelsGuard<'_>, anyhow::Error> {
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels));
+        let mut channels = vec![Vec::new(); num_channels];
+        self.deinterleave_into_i16(&mut channels)?;
+        Ok(ChannelsGuard {
+            format: self,
+            channels,
+        })
+    }
+
+    /// `to_ndarray_i16` returns the 16-bit samples as an array of shape `(frames, channels)`.
+    ///
+    /// Requires the `ndarray` feature. A trailing partial frame is dropped.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = (0..12_i16).flat_map(|sample| sample.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 3, 16_000, 16)?;
+    ///
+    ///     let array = encoding.to_ndarray_i16()?;
+    ///     assert_eq!(array.dim(), (encoding.frame_count() as usize, 3));
+    ///     assert_eq!(array[[2, 1]], encoding.sample_at_i16(2, 1)?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray_i16(&self) -> Result<ndarray::Array2<i16>, anyhow::Error> {
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let mut samples = self.samples_i16()?;
+        let frames = samples.len() / num_channels;
+        samples.truncate(frames * num_channels);
+        Ok(ndarray::Array2::from_shape_vec(
+            (frames, num_channels),
+            samples,
+        )?)
+    }
+
+    /// `data_offset` returns the byte offset of the audio data in the encoded file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     assert_eq!(encoding.data_offset(), 44);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn data_offset(&self) -> u64 {
+        self.header().map_or(44, |header| header.len() as u64)
+    }
+
+    /// `has_canonical_layout` checks if the file has the canonical 44-byte header.
+    ///
+    /// The audio data of such a file starts right after the format chunk, so readers can locate
+    /// it without walking the chunks (e.g. when memory-mapping the file).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let plain = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+    ///     assert!(Format::decode_bytes(&plain)?.has_canonical_layout());
+    ///
+    ///     let fact = b"fact\x04\x00\x00\x00\x08\x00\x00\x00";
+    ///     let mut bytes = [&plain[..36], fact, &plain[36..]].concat();
+    ///     let total_size = (bytes.len() as u32 - 8).to_le_bytes();
+    ///     bytes[4..8].copy_from_slice(&total_size);
+    ///     assert!(!Format::decode_bytes(&bytes)?.has_canonical_layout());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn has_canonical_layout(&self) -> bool {
+        self.leading_chunks.is_empty()
+            && self.broadcast_info.is_none()
+            && u32::from_le_bytes(self.fmt_chunk_size) == 16
+            && self.data_offset() == 44
+    }
+
+    /// `validate_serialized_structure` walks every chunk of [`Format::to_bytes`] to check the
+    /// RIFF structure that would be written.
+    ///
+    /// Unlike [`Format::check`], which only looks at the fixed fields, this verifies that the
+    /// declared chunk sizes tile the serialized file exactly, that a `data` chunk exists, and that
+    /// the chunks add up to `total_size + 8` bytes. Serializing always pads odd-sized chunks, so
+    /// the layout of the file a `Format` was decoded from is not checked, only the size fields as
+    /// they stand.
+    ///
+    /// # Errors
+    ///
+    /// If the structure is inconsistent or the value cannot fit when performing type
+    /// conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     encoding.validate_serialized_structure()?;
+    ///
+    ///     encoding.total_size = 100_u32.to_le_bytes();
+    ///     encoding.check()?;
+    ///     assert!(encoding.validate_serialized_structure().is_err());
+    ///
+    ///     encoding.total_size = 52_u32.to_le_bytes();
+    ///     encoding.data_size = 18_u32.to_le_bytes();
+    ///     assert!(encoding.validate_serialized_structure().is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn validate_serialized_structure(&self) -> Result<(), anyhow::Error> {
+        let bytes = self.to_bytes()?;
+        let mut offset = 12;
+        let mut has_data = false;
+        while offset + 1 < bytes.len() {
+            if offset + 8 > bytes.len() {
+                return Err(anyhow::anyhow!(
+                    "RIFF structure has a truncated chunk header at offset {offset}."
+                ));
+            }
+
+            let tag = &bytes[offset..offset + 4];
+            let size = usize::try_from(le_u32(&bytes, offset + 4))?;
+            let remaining = bytes.len() - offset - 8;
+            if size > remaining {
+                let tag = String::from_utf8_lossy(tag);
+                return Err(anyhow::anyhow!(
+                    "RIFF chunk \"{tag}\" at offset {offset} claims {size} bytes, got {remaining} remaining instead."
+                ));
+            }
+
+            has_data |= tag == b"data";
+            offset += 8 + size + size % 2;
+        }
+
+        if !has_data {
+            return Err(anyhow::anyhow!("RIFF structure requires a \"data\" chunk."));
+        }
+
+        let file_size = bytes.len();
+        let expected = u64::from(u32::from_le_bytes(self.total_size)) + 8;
+        if expected != file_size as u64 {
+            return Err(anyhow::anyhow!(
+                "RIFF chunks add up to {file_size} bytes, but the total size of bytes 5 - 8 implies {expected} bytes."
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `analyze` computes the peak and RMS level of the 16-bit audio data.
+    ///
+    /// See [`analyze_stream`] for files that do not fit in memory.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [3_i16, -4].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let analysis = Format::encode(data, 1, 16_000, 16)?.analyze()?;
+    ///     assert_eq!(analysis.peak, 4);
+    ///     assert_eq!(analysis.sample_count, 2);
+    ///     assert!((analysis.rms - 12.5_f64.sqrt()).abs() < 1e-9);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn analyze(&self) -> Result<Analysis, anyhow::Error> {
+        self.require_16_bits()?;
+
+        let mut accumulator = AnalysisAccumulator::default();
+        accumulator.add(&self.data);
+        Ok(accumulator.finish())
+    }
+
+    /// `peak_dbfs` returns the peak level of the audio data in decibels full scale.
+    ///
+    /// The peak is taken over the normalized samples, so full scale is 0 dBFS at every bit
+    /// depth. Digital silence reads negative infinity.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let tone = |amplitude: f64| -> Result<Format, anyhow::Error> {
+    ///         let data = (0..1_600)
+    ///             .map(|n| amplitude * (f64::from(n) * std::f64::consts::TAU / 16.0).sin())
+    ///             .flat_map(|x| (x.round() as i16).to_le_bytes())
+    ///             .collect();
+    ///         Format::encode(data, 1, 16_000, 16)
+    ///     };
+    ///
+    ///     assert!(tone(32_767.0)?.peak_dbfs()?.abs() < 0.01);
+    ///     assert!((tone(16_384.0)?.peak_dbfs()? + 6.02).abs() < 0.01);
+    ///     assert_eq!(tone(0.0)?.peak_dbfs()?, f64::NEG_INFINITY);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn peak_dbfs(&self) -> Result<f64, anyhow::Error> {
+        let peak = self
+            .normalized_f32()?
+            .iter()
+            .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+        Ok(20.0 * f64::from(peak).log10())
+    }
+
+    /// `headroom_db` returns how many decibels of gain can be added before the peak clips.
+    ///
+    /// This is the negated [`Format::peak_dbfs`]. Digital silence has unlimited headroom and
+    /// returns `f64::INFINITY`.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let data = [16_384_i16, -8_000, 0].iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///     let encoding = Format::encode(data, 1, 16_000, 16)?;
+    ///     assert!((encoding.headroom_db()? - 6.02).abs() < 0.01);
+    ///
+    ///     let silence = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     assert_eq!(silence.headroom_db()?, f64::INFINITY);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn headroom_db(&self) -> Result<f64, anyhow::Error> {
+        Ok(-self.peak_dbfs()?)
+    }
+
+    /// `stereo_correlation` returns the Pearson correlation between the left and right channels.
+    ///
+    /// The result is in `[-1, 1]`. Values near 1 collapse cleanly to mono, values near -1
+    /// indicate out-of-phase channels that cancel in mono. It is 0 when either channel is
+    /// constant.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data does not have exactly 2 channels or is neither 8, 16, 24 or 32-bit
+    /// integer PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{interleave_i16, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let left: Vec<i16> = (0..1_600).map(|n| ((n * 37) % 2_000 - 1_000) as i16).collect();
+    ///     let inverted: Vec<i16> = left.iter().map(|x| -x).collect();
+    ///
+    ///     let identical = Format::encode(interleave_i16(&[&left, &left])?, 2, 16_000, 16)?;
+    ///     assert!((identical.stereo_correlation()? - 1.0).abs() < 1e-9);
+    ///
+    ///     let opposed = Format::encode(interleave_i16(&[&left, &inverted])?, 2, 16_000, 16)?;
+    ///     assert!((opposed.stereo_correlation()? + 1.0).abs() < 1e-9);
+    ///
+    ///     assert_eq!(Format::encode(vec![0u8; 8], 2, 16_000, 16)?.stereo_correlation()?, 0.0);
+    ///     assert!(Format::encode(vec![0u8; 8], 1, 16_000, 16)?.stereo_correlation().is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn stereo_correlation(&self) -> Result<f64, anyhow::Error> {
+        let num_channels = u16::from_le_bytes(self.num_channels);
+        if num_channels != 2 {
+            return Err(anyhow::anyhow!(
+                "Stereo correlation requires 2 channels, got {num_channels} instead."
+            ));
+        }
+
+        let samples = self.normalized_f32()?;
+        let frames = samples.chunks_exact(2);
+        #[allow(clippy::cast_precision_loss)]
+        let count = frames.len().max(1) as f64;
+        let (left_sum, right_sum) = frames.clone().fold((0.0, 0.0), |(l, r), frame| {
+            (l + f64::from(frame[0]), r + f64::from(frame[1]))
+        });
+        let (left_mean, right_mean) = (left_sum / count, right_sum / count);
+
+        let (mut covariance, mut left_variance, mut right_variance) = (0.0, 0.0, 0.0);
+        for frame in frames {
+            let left = f64::from(frame[0]) - left_mean;
+            let right = f64::from(frame[1]) - right_mean;
+            covariance += left * right;
+            left_variance += left * left;
+            right_variance += right * right;
+        }
+
+        let norm = (left_variance * right_variance).sqrt();
+        if norm == 0.0 {
+            return Ok(0.0);
+        }
+        Ok((covariance / norm).clamp(-1.0, 1.0))
+    }
+
+    /// `zero_crossing_rate` returns the fraction of adjacent samples that change sign.
+    ///
+    /// Zero counts as positive, so silence and constant signals of either sign have a rate of 0.
+    /// The rate is computed per channel and averaged over the channels.
+    ///
+    /// # Errors
+    ///
+    /// If the audio data is not 16 bits per sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encode = |samples: &[i16]| {
+    ///         let data = samples.iter().flat_map(|x| x.to_le_bytes()).collect();
+    ///         Format::encode(data, 1, 16_000, 16)
+    ///     };
+    ///
+    ///     let square: Vec<i16> = (0..1_600).map(|n| if n % 2 == 0 { 1_000 } else { -1_000 }).collect();
+    ///     assert_eq!(encode(&square)?.zero_crossing_rate()?, 1.0);
+    ///     assert_eq!(encode(&[0; 1_600])?.zero_crossing_rate()?, 0.0);
+    ///     assert_eq!(encode(&[500; 1_600])?.zero_crossing_rate()?, 0.0);
+    ///     assert_eq!(encode(&[1, -1, -1, -1, 1])?.zero_crossing_rate()?, 0.5);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn zero_crossing_rate(&self) -> Result<f64, anyhow::Error> {
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let samples = self.samples_i16()?;
+        let frames = samples.len() / num_channels;
+        if frames < 2 {
+            return Ok(0.0);
+        }
+
+        let crossings = samples
+            .iter()
+            .zip(&samples[num_channels..])
+            .filter(|&(&prev, &next)| (prev < 0) != (next < 0))
+            .count();
+
+        #[allow(clippy::cast_precision_loss)]
+        Ok(crossings as f64 / ((frames - 1) * num_channels) as f64)
+    }
+
+    /// `find_chunk` returns the contents of the first chunk with the given tag.
+    ///
+    /// Chunks are searched in file order. The format chunk and the chunks modeled by dedicated
+    /// fields (such as `smpl`) are serialized from those fields, only the audio data is borrowed.
+    /// Returns `None` if no chunk has the tag or the chunks cannot be serialized.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - Chunk tag (e.g. `b"LIST"`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleLoop};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let plain = Format::encode(vec![1u8; 16], 1, 16_000, 16)?.to_bytes()?;
+    ///     let list = b"LIST\x04\x00\x00\x00INFO";
+    ///     let mut bytes = [&plain[..36], list, &plain[36..]].concat();
+    ///     let total_size = (bytes.len() as u32 - 8).to_le_bytes();
+    ///     bytes[4..8].copy_from_slice(&total_size);
+    ///
+    ///     let mut decoding = Format::decode_bytes(&bytes)?;
+    ///     assert_eq!(decoding.find_chunk(b"LIST").as_deref(), Some(&b"INFO"[..]));
+    ///     assert_eq!(decoding.find_chunk(b"data").as_deref(), Some(&[1u8; 16][..]));
+    ///     assert_eq!(decoding.find_chunk(b"fmt ").as_deref(), Some(&plain[20..36]));
+    ///     assert_eq!(decoding.find_chunk(b"smpl"), None);
+    ///
+    ///     decoding.set_sample_loops(vec![SampleLoop::new(0, 7)])?;
+    ///     assert_eq!(decoding.find_chunk(b"smpl").map(|smpl| smpl.len()), Some(60));
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn find_chunk(&self, tag: &[u8; 4]) -> Option<Cow<'_, [u8]>> {
+        let (header_order, trailer_order) = self.chunk_order_sections();
+        let find = |chunks: Vec<(ChunkKind, Vec<u8>)>, chunk_order| {
+            in_chunk_order(chunks, chunk_order)
+                .into_iter()
+                .find(|chunk| chunk.starts_with(tag))
+                .map(|chunk| chunk[8..8 + le_u32(&chunk, 4) as usize].to_vec())
+        };
+
+        if let Some(contents) = find(self.header_chunks().ok()?, header_order) {
+            return Some(Cow::Owned(contents));
+        }
+
+        if *tag == self.data_tag {
+            return Some(Cow::Borrowed(&self.data));
+        }
+
+        find(self.trailer_chunks().ok()?, trailer_order).map(Cow::Owned)
+    }
+
+    /// `sample_format` resolves the sample format from the format code and bits per sample.
+    ///
+    /// The sub-format of the extensible format is resolved as well, so matching on the result
+    /// covers every supported layout.
+    ///
+    /// # Errors
+    ///
+    /// If the combination does not match a [`SampleFormat`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     assert_eq!(encoding.sample_format()?, SampleFormat::I16);
+    ///
+    ///     let encoding = Format::encode_sample_format(vec![0u8; 24], 6, 48_000, SampleFormat::F32)?;
+    ///     assert_eq!(encoding.sample_format()?, SampleFormat::F32);
+    ///
+    ///     let encoding = Format::encode(vec![0u8; 16], 1, 16_000, 12)?;
+    ///     assert!(encoding.sample_format().is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sample_format(&self) -> Result<SampleFormat, anyhow::Error> {
+        let fmt_code = self.sample_fmt_code();
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        match (fmt_code, bits_per_sample) {
+            (1, 8) => Ok(SampleFormat::U8),
+            (1, 16) => Ok(SampleFormat::I16),
+            (1, 24) => Ok(SampleFormat::I24),
+            (1, 32) => Ok(SampleFormat::I32),
+            (3, 32) => Ok(SampleFormat::F32),
+            (3, 64) => Ok(SampleFormat::F64),
+            _ => Err(anyhow::anyhow!(
+                "Unsupported sample format, got format code {fmt_code} with {bits_per_sample} bits per sample instead."
+            )),
+        }
+    }
+
+    // Return the format code of the samples, resolving the sub-format of the extensible format.
+    fn sample_fmt_code(&self) -> u16 {
+        let fmt_code = u16::from_le_bytes(self.fmt_code);
+        match (fmt_code, self.fmt_extension.get(8..24)) {
+            (0xFFFE, Some(guid)) if guid[2..] == SUBFORMAT_GUID_TAIL => {
+                u16::from_le_bytes([guid[0], guid[1]])
+            }
+            _ => fmt_code,
+        }
+    }
+
+    // Return the audio data as interleaved samples normalized to [-1, 1).
+    //
+    // # Errors
+    //
+    // If the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM.
+    #[allow(clippy::cast_possible_truncation)]
+    fn normalized_f32(&self) -> Result<Vec<f32>, anyhow::Error> {
+        self.require_byte_aligned()?;
+        let fmt_code = self.sample_fmt_code();
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        match (fmt_code, bits_per_sample) {
+            (1, 8 | 16 | 24 | 32) | (3, 32) => Ok(self
+                .data
+                .chunks_exact(usize::from(bits_per_sample / 8))
+                .map(|bytes| sample_to_f32(bytes, fmt_code == 3))
+                .collect()),
+            _ => Err(anyhow::anyhow!(
+                "Operation requires 8, 16, 24 or 32-bit integer PCM or 32-bit float PCM, got format code {fmt_code} with {bits_per_sample} bits per sample instead."
+            )),
+        }
+    }
+
+    // Return the normalized samples handed out by the public float accessors.
+    //
+    // # Arguments
+    //
+    // * `sanitize_floats` - Whether to pass the samples through `sanitize_f32`.
+    //
+    // # Errors
+    //
+    // If the audio data is neither 8, 16, 24 or 32-bit integer PCM nor 32-bit float PCM.
+    fn accessor_f32(&self, sanitize_floats: bool) -> Result<Vec<f32>, anyhow::Error> {
+        let mut samples = self.normalized_f32()?;
+        if sanitize_floats {
+            for sample in &mut samples {
+                *sample = sanitize_f32(*sample);
+            }
+        }
+        Ok(samples)
+    }
+
+    // Encode normalized samples in the format, number of channels and metadata of this file.
+    //
+    // # Arguments
+    //
+    // * `samples` - Interleaved audio samples normalized to [-1, 1).
+    // * `sampling_rate` - Sampling rate of the samples.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    #[cfg(feature = "std")]
+    fn with_normalized_f32(
+        &self,
+        samples: &[f32],
+        sampling_rate: u32,
+    ) -> Result<Format, anyhow::Error> {
+        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
+        let width = usize::from(bits_per_sample / 8);
+        let mut data = Vec::with_capacity(samples.len() * width);
+        let float = self.sample_fmt_code() == 3;
+        for &sample in samples {
+            f32_to_sample(sample, width, float, &mut data);
+        }
+
+        let mut format = Format::encode(
+            data,
+            u16::from_le_bytes(self.num_channels),
+            sampling_rate,
+            bits_per_sample,
+        )?;
+        format.fmt_chunk_size = self.fmt_chunk_size;
+        format.fmt_code = self.fmt_code;
+        format.fmt_extension.clone_from(&self.fmt_extension);
+        format.copy_metadata_from(self)?;
+        Ok(format)
+    }
+
+    /// `resampled_frame_count` predicts the number of frames [`Format::resample`] produces.
+    ///
+    /// The count is `frame_count * sampling_rate / original_sampling_rate` rounded down, computed
+    /// with integer math so it matches the resampled file exactly. A file with a sampling rate of
+    /// 0 yields 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_rate` - Sampling rate of the resampled audio data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     for (source_rate, frames) in [(44_100, 44_099), (48_000, 12_345), (22_050, 1), (8_000, 7)] {
+    ///         let encoding = Format::encode(vec![0u8; frames * 4], 2, source_rate, 16)?;
+    ///         for sampling_rate in [8_000, 16_000, 22_050, 44_100, 48_000, 96_000] {
+    ///             let resampled = encoding.resample(sampling_rate)?;
+    ///             assert_eq!(
+    ///                 encoding.resampled_frame_count(sampling_rate),
+    ///                 resampled.frame_count()
+    ///             );
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn resampled_frame_count(&self, sampling_rate: u32) -> u64 {
+        resampled_frames(
+            self.frame_count(),
+            u32::from_le_bytes(self.sampling_rate),
+            sampling_rate,
+        )
+    }
+
+    /// `resample` converts the audio data to another sampling rate.
+    ///
+    /// Samples are linearly interpolated, the output has `frame_count * sampling_rate /
+    /// original_sampling_rate` frames (rounded down). The bit depth and sample format are
+    /// preserved, and so are loop points, cue points and the broadcast time reference, rescaled
+    /// to the new rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_rate` - Sampling rate of the resampled audio data.
+    ///
+    /// # Errors
+    ///
+    /// If either sampling rate is 0, or the audio data is neither 8, 16, 24 or 32-bit integer
+    /// PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 3 * 2 * 441], 2, 44_100, 24)?;
+    ///     let resampled = encoding.resample(48_000)?;
+    ///
+    ///     assert_eq!(u16::from_le_bytes(resampled.bits_per_sample), 24);
+    ///     assert_eq!(u32::from_le_bytes(resampled.sampling_rate), 48_000);
+    ///     assert_eq!(resampled.frame_count(), 480);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Transforms return a new file rather than modifying this one, so they chain:
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 3 * 2 * 441], 2, 44_100, 24)?;
+    ///     let processed = encoding.resample(16_000)?.ensure_16bit()?.highpass(80.0)?;
+    ///
+    ///     processed.check()?;
+    ///     assert_eq!(u16::from_le_bytes(processed.bits_per_sample), 16);
+    ///     assert_eq!(u32::from_le_bytes(processed.sampling_rate), 16_000);
+    ///     assert_eq!(processed.frame_count(), 160);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Output frames are located by exact rational positions, so the phase of a long tone does
+    /// not drift:
+    ///
+    /// ```
+    /// use wavepcm::{Format, SampleFormat};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let tone = |rate: u32, n: u64| {
+    ///         0.5 * (std::f64::consts::TAU * 1_000.0 * n as f64 / f64::from(rate)).sin()
+    ///     };
+    ///     let data = (0..44_100 * 600)
+    ///         .flat_map(|n| (tone(44_100, n) as f32).to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode_sample_format(data, 1, 44_100, SampleFormat::F32)?;
+    ///
+    ///     let resampled = encoding.resample(48_000)?;
+    ///     assert_eq!(resampled.frame_count(), 48_000 * 600);
+    ///     // The last frame has no following input frame to interpolate towards.
+    ///     let frames = resampled.data.chunks_exact(4).enumerate();
+    ///     for (n, bytes) in frames.skip(48_000 * 599).take(48_000 - 1) {
+    ///         let sample = f32::from_le_bytes(bytes.try_into()?);
+    ///         assert!((f64::from(sample) - tone(48_000, n as u64)).abs() < 0.005);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn resample(&self, sampling_rate: u32) -> Result<Format, anyhow::Error> {
+        let source_rate = u32::from_le_bytes(self.sampling_rate);
+        Resampler::new(source_rate, sampling_rate, ResampleQuality::Linear)?.process(self)
+    }
+
+    /// `resample_anti_aliased` converts the audio data to another sampling rate, lowpass
+    /// filtering it first when the rate decreases.
+    ///
+    /// When the target rate is below the source rate, two cascaded biquads remove content above
+    /// the new Nyquist frequency before the samples are linearly interpolated as in
+    /// [`Format::resample`], so it cannot alias to lower frequencies. The returned flag tells
+    /// whether the filter was applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_rate` - Sampling rate of the resampled audio data.
+    ///
+    /// # Errors
+    ///
+    /// If either sampling rate is 0, or the audio data is neither 8, 16, 24 or 32-bit integer
+    /// PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     // A 15 kHz tone aliases to 1 kHz at 16 kHz.
+    ///     let data = (0..4_800)
+    ///         .map(|n| 16_000.0 * (f64::from(n) * 15_000.0 * std::f64::consts::TAU / 48_000.0).sin())
+    ///         .flat_map(|x| (x.round() as i16).to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode(data, 1, 48_000, 16)?;
+    ///
+    ///     let peak = |format: &Format| {
+    ///         format.data[200..]
+    ///             .chunks_exact(2)
+    ///             .map(|pair| i16::from_le_bytes([pair[0], pair[1]]).unsigned_abs())
+    ///             .max()
+    ///             .unwrap_or(0)
+    ///     };
+    ///     let (filtered, anti_aliased) = encoding.resample_anti_aliased(16_000)?;
+    ///     assert!(anti_aliased);
+    ///     assert_eq!(filtered.frame_count(), 1_600);
+    ///     assert!(peak(&filtered) < peak(&encoding.resample(16_000)?) / 10);
+    ///
+    ///     let (upsampled, anti_aliased) = encoding.resample_anti_aliased(96_000)?;
+    ///     assert!(!anti_aliased);
+    ///     assert!(upsampled == encoding.resample(96_000)?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn resample_anti_aliased(
+        &self,
+        sampling_rate: u32,
+    ) -> Result<(Format, bool), anyhow::Error> {
+        let source_rate = u32::from_le_bytes(self.sampling_rate);
+        let resampler = Resampler::new(source_rate, sampling_rate, ResampleQuality::AntiAliased)?;
+        Ok((resampler.process(self)?, resampler.lowpass.is_some()))
+    }
+
+    /// `resample_path` returns how [`Format::resample_poly`] converts to a sampling rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_rate` - Sampling rate of the resampled audio data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, ResamplePath};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 16], 1, 48_000, 16)?;
+    ///     assert_eq!(encoding.resample_path(16_000), ResamplePath::Decimate(3));
+    ///     assert_eq!(encoding.resample_path(96_000), ResamplePath::Interpolate(2));
+    ///     assert_eq!(encoding.resample_path(44_100), ResamplePath::General);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn resample_path(&self, sampling_rate: u32) -> ResamplePath {
+        let source_rate = u32::from_le_bytes(self.sampling_rate);
+        match (source_rate, sampling_rate) {
+            (0, _) | (_, 0) => ResamplePath::General,
+            _ if source_rate > sampling_rate && source_rate.is_multiple_of(sampling_rate) => {
+                ResamplePath::Decimate(source_rate / sampling_rate)
+            }
+            _ if sampling_rate > source_rate && sampling_rate.is_multiple_of(source_rate) => {
+                ResamplePath::Interpolate(sampling_rate / source_rate)
+            }
+            _ => ResamplePath::General,
+        }
+    }
+
+    /// `resample_poly` converts the audio data to another sampling rate, with a fast path for
+    /// integer ratios.
+    ///
+    /// When the source rate is a multiple of the target rate, the audio data is lowpass
+    /// filtered below the new Nyquist frequency and decimated. When the target rate is a
+    /// multiple of the source rate, frames are linearly interpolated at fixed fractions. Any
+    /// other ratio falls back to [`Format::resample`]. See [`Format::resample_path`] for the
+    /// path taken; the output frame count and metadata handling match [`Format::resample`].
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_rate` - Sampling rate of the resampled audio data.
+    ///
+    /// # Errors
+    ///
+    /// If either sampling rate is 0, or the audio data is neither 8, 16, 24 or 32-bit integer
+    /// PCM nor 32-bit float PCM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{Format, ResamplePath};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 2 * 4_800], 1, 48_000, 16)?;
+    ///     assert_eq!(encoding.resample_path(16_000), ResamplePath::Decimate(3));
+    ///
+    ///     let resampled = encoding.resample_poly(16_000)?;
+    ///     assert_eq!(u32::from_le_bytes(resampled.sampling_rate), 16_000);
+    ///     assert_eq!(resampled.frame_count(), 1_600);
+    ///     assert_eq!(encoding.resample_poly(96_000)?.frame_count(), 9_600);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn resample_poly(&self, sampling_rate: u32) -> Result<Format, anyhow::Error> {
+        let path = self.resample_path(sampling_rate);
+        let factor = match path {
+            ResamplePath::General => return self.resample(sampling_rate),
+            ResamplePath::Decimate(factor) | ResamplePath::Interpolate(factor) => factor as usize,
+        };
+
+        let samples = self.normalized_f32()?;
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let frame_count = samples.len() / num_channels;
+        let samples = &samples[..frame_count * num_channels];
+
+        let output: Vec<f32> = if let ResamplePath::Decimate(_) = path {
+            let lowpass =
+                BiquadCoeffs::anti_alias(u32::from_le_bytes(self.sampling_rate), sampling_rate);
+            anti_alias(samples, num_channels, &lowpass)
+                .chunks_exact(num_channels)
+                .step_by(factor)
+                .take(frame_count / factor)
+                .flatten()
+                .map(|&sample| sample as f32)
+                .collect()
+        } else {
+            let mut output = Vec::with_capacity(samples.len() * factor);
+            for frame in 0..frame_count {
+                let next = (frame + 1).min(frame_count - 1);
+                for step in 0..factor {
+                    let frac = step as f32 / factor as f32;
+                    for channel in 0..num_channels {
+                        let current = samples[frame * num_channels + channel];
+                        let following = samples[next * num_channels + channel];
+                        output.push(current + (following - current) * frac);
+                    }
+                }
+            }
+            output
+        };
+
+        self.with_resampled_f32(&output, sampling_rate)
+    }
+
+    // Encode resampled normalized samples, rescaling loop points and the broadcast time
+    // reference to the new sampling rate.
+    //
+    // # Arguments
+    //
+    // * `samples` - Interleaved audio samples normalized to [-1, 1).
+    // * `sampling_rate` - Sampling rate of the samples.
+    //
+    // # Errors
+    //
+    // If the value cannot fit when performing type conversion.
+    #[cfg(feature = "std")]
+    fn with_resampled_f32(
+        &self,
+        samples: &[f32],
+        sampling_rate: u32,
+    ) -> Result<Format, anyhow::Error> {
+        let source_rate = u32::from_le_bytes(self.sampling_rate);
+        let mut format = self.with_normalized_f32(samples, sampling_rate)?;
+        let rescale = |value: u64| value * u64::from(sampling_rate) / u64::from(source_rate);
+        for sample_loop in &mut format.sample_loops {
+            sample_loop.start = rescale(u64::from(sample_loop.start)).try_into()?;
+            sample_loop.end = rescale(u64::from(sample_loop.end)).try_into()?;
+        }
+        if let Some(sampler) = &mut format.sampler {
+            sampler.sample_period = Sampler::new(sampling_rate).sample_period;
+        }
+        for cue_point in &mut format.cue_points {
+            cue_point.position = rescale(u64::from(cue_point.position)).try_into()?;
+            cue_point.sample_offset = rescale(u64::from(cue_point.sample_offset)).try_into()?;
+        }
+        if let Some(broadcast_info) = &mut format.broadcast_info {
+            broadcast_info.time_reference = rescale(broadcast_info.time_reference);
+        }
+
+        Ok(format)
+    }
+
+    // Return the first `LIST` chunk of type `INFO`, creating it after the audio data if needed.
+    fn info_chunk_mut(&mut self) -> &mut Chunk {
+        let is_info = |chunk: &Chunk| chunk.tag == *b"LIST" && chunk.data.starts_with(b"INFO");
+        let position = (
+            self.leading_chunks.iter().position(is_info),
+            self.trailing_chunks.iter().position(is_info),
+        );
+
+        match position {
+            (Some(idx), _) => &mut self.leading_chunks[idx],
+            (None, Some(idx)) => &mut self.trailing_chunks[idx],
+            (None, None) => {
+                self.trailing_chunks.push(Chunk {
+                    tag: *b"LIST",
+                    data: b"INFO".to_vec(),
+                });
+                self.trailing_chunks
+                    .last_mut()
+                    .expect("chunk was just pushed")
+            }
+        }
+    }
+
+    /// `metadata` returns a value of the `LIST` `INFO` chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Four-character key (e.g. "INAM" for the title).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     assert_eq!(encoding.metadata("INAM"), None);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn metadata(&self, key: &str) -> Option<String> {
+        self.leading_chunks
+            .iter()
+            .chain(&self.trailing_chunks)
+            .filter(|chunk| chunk.tag == *b"LIST")
+            .filter_map(|chunk| parse_info(&chunk.data))
+            .flatten()
+            .find(|(entry, _)| entry == key.as_bytes())
+            .map(|(_, value)| {
+                let end = value
+                    .iter()
+                    .position(|&byte| byte == 0)
+                    .unwrap_or(value.len());
+                String::from_utf8_lossy(&value[..end]).into_owned()
+            })
+    }
+
+    /// `set_metadata` sets a value of the `LIST` `INFO` chunk.
+    ///
+    /// The chunk is created after the audio data if the file has none, and the `total_size`
+    /// field is updated to account for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Four-character key (e.g. "INAM" for the title).
+    /// * `value` - Value, written with a NUL terminator.
+    ///
+    /// # Errors
+    ///
+    /// If the key is not four ASCII characters or the existing chunk is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let path = std::env::temp_dir().join("wavepcm_set_metadata.wav");
+    ///     let path = path.to_str().unwrap();
+    ///
+    ///     let mut encoding = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     encoding.set_metadata("INAM", "Song")?;
+    ///     encoding.set_metadata("IART", "Band")?;
+    ///     encoding.write(path)?;
+    ///
+    ///     let decoding = Format::decode(path)?;
+    ///     assert_eq!(decoding.metadata("INAM").as_deref(), Some("Song"));
+    ///     assert_eq!(decoding.metadata("IART").as_deref(), Some("Band"));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> Result<(), anyhow::Error> {
+        let key = match <[u8; 4]>::try_from(key.as_bytes()) {
+            Ok(key) if key.is_ascii() => key,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Metadata key must be four ASCII characters, got {key:?} instead."
+                ))
+            }
+        };
+
+        let chunk = self.info_chunk_mut();
+        let Some(mut entries) = parse_info(&chunk.data) else {
+            return Err(anyhow::anyhow!("LIST INFO chunk is malformed."));
+        };
+
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.push(0);
+        match entries.iter_mut().find(|(entry, _)| *entry == key) {
+            Some((_, existing)) => *existing = bytes,
+            None => entries.push((key, bytes)),
+        }
+        chunk.data = build_info(&entries)?;
+
+        self.sync_total_size()
+    }
+
+    /// `remove_metadata` removes a value of the `LIST` `INFO` chunk.
+    ///
+    /// The chunk is removed once it holds no values, and the `total_size` field is updated.
     ///
     /// # Arguments
     ///
-    /// * `data` - Raw audio data.
-    /// * `num_channels` - Number of channels in the audio data.
-    /// * `sampling_rate` - Sampling rate in the audio data.
-    /// * `bits_per_sample` - Bits per sample in the audio data.
+    /// * `key` - Four-character key (e.g. "INAM" for the title).
     ///
     /// # Errors
     ///
-    /// If the value cannot fit when performing type conversion.
+    /// If the existing chunk is malformed.
     ///
     /// # Example
     ///
     /// ```
     /// use wavepcm::Format;
     ///
-    /// let data = vec![1u8; 16];
-    /// let num_channels = 1;
-    /// let sampling_rate = 16_000;
-    /// let bits_per_sample = 16;
-    /// let encoding = Format::encode(data, num_channels, sampling_rate, bits_per_sample);
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let mut encoding = Format::encode(vec![0u8; 16], 1, 16_000, 16)?;
+    ///     encoding.set_metadata("INAM", "Song")?;
+    ///     encoding.remove_metadata("INAM")?;
+    ///
+    ///     assert_eq!(encoding.metadata("INAM"), None);
+    ///     assert_eq!(encoding.to_bytes()?.len(), 44 + 16);
+    ///     Ok(())
+    /// }
     /// ```
-    pub fn encode(
-        data: Vec<u8>,
-        num_channels: u16,
-        sampling_rate: u32,
-        bits_per_sample: u16,
-    ) -> Result<Self, anyhow::Error> {
-        let size: u32 = data.len().try_into()?;
+    pub fn remove_metadata(&mut self, key: &str) -> Result<(), anyhow::Error> {
+        let chunk = self.info_chunk_mut();
+        let Some(mut entries) = parse_info(&chunk.data) else {
+            return Err(anyhow::anyhow!("LIST INFO chunk is malformed."));
+        };
 
-        let riff_tag = "RIFF".as_bytes().try_into()?;
-        let total_size = (size + 36).to_le_bytes();
-        let wave_tag = "WAVE".as_bytes().try_into()?;
-        let fmt_chunk_tag = "fmt ".as_bytes().try_into()?;
-        let fmt_chunk_size = 16_u32.to_le_bytes();
-        let fmt_code = 1_u16.to_le_bytes();
-        let byte_rate = (sampling_rate * u32::from(num_channels) * u32::from(bits_per_sample) / 8)
-            .to_le_bytes();
-        let block_alignment = (num_channels * bits_per_sample / 8).to_le_bytes();
-        let data_tag = "data".as_bytes().try_into()?;
-        let data_size = size.to_le_bytes();
+        entries.retain(|(entry, _)| entry != key.as_bytes());
+        chunk.data = build_info(&entries)?;
 
-        Ok(Format {
-            riff_tag,
-            total_size,
-            wave_tag,
-            fmt_chunk_tag,
-            fmt_chunk_size,
-            fmt_code,
-            num_channels: num_channels.to_le_bytes(),
-            sampling_rate: sampling_rate.to_le_bytes(),
-            byte_rate,
-            block_alignment,
-            bits_per_sample: bits_per_sample.to_le_bytes(),
-            data_tag,
-            data_size,
-            data,
-        })
+        let is_empty_info = |chunk: &Chunk| chunk.tag == *b"LIST" && chunk.data == b"INFO";
+        self.leading_chunks.retain(|chunk| !is_empty_info(chunk));
+        self.trailing_chunks.retain(|chunk| !is_empty_info(chunk));
+
+        self.sync_total_size()
     }
 
-    /// `decode` decodes WAVE PCM file.
+    /// `assert_pcm` checks that the audio data is plain integer PCM.
     ///
-    /// # Arguments
-    ///
-    /// * `path` - A path to the WAV PCM file.
+    /// Both format code 1 and the extensible format (code `0xFFFE`) with the PCM sub-format are
+    /// accepted.
     ///
     /// # Errors
     ///
-    /// This function will return an error if `path` does not already exist.
-    /// Other errors may also be returned according to `OpenOptions::open`.
+    /// If the audio data is in any other format, such as float or compressed audio.
     ///
     /// # Example
     ///
@@ -168,54 +8411,46 @@ impl Format {
     /// use wavepcm::Format;
     ///
     /// fn main() -> Result<(), anyhow::Error> {
-    ///     let decoding = Format::decode("sample.wav")?;
+    ///     let mut encoding = Format::encode(vec![0u8; 16], 1, 16_000, 32)?;
+    ///     encoding.assert_pcm()?;
+    ///
+    ///     encoding.fmt_code = 3_u16.to_le_bytes();
+    ///     assert!(encoding.assert_pcm().is_err());
     ///     Ok(())
     /// }
     /// ```
-    pub fn decode(path: &str) -> Result<Self, anyhow::Error> {
-        let file = File::open(path)?;
-        let mut bufr = BufReader::new(file);
-
-        let riff_tag = read4(&mut bufr);
-        let total_size = read4(&mut bufr);
-        let wave_tag = read4(&mut bufr);
-        let fmt_chunk_tag = read4(&mut bufr);
-        let fmt_chunk_size = read4(&mut bufr);
-        let fmt_code = read2(&mut bufr);
-        let num_channels = read2(&mut bufr);
-        let sampling_rate = read4(&mut bufr);
-        let byte_rate = read4(&mut bufr);
-        let block_alignment = read2(&mut bufr);
-        let bits_per_sample = read2(&mut bufr);
-        let data_tag = read4(&mut bufr);
-        let data_size = read4(&mut bufr);
-        let data = readn(&mut bufr, u32::from_le_bytes(data_size))?;
+    pub fn assert_pcm(&self) -> Result<(), anyhow::Error> {
+        let fmt_code = u16::from_le_bytes(self.fmt_code);
+        let is_pcm = match fmt_code {
+            1 => true,
+            0xFFFE => {
+                self.fmt_extension.get(8..10) == Some(&[0x01, 0x00][..])
+                    && self.fmt_extension.get(10..24) == Some(&SUBFORMAT_GUID_TAIL[..])
+            }
+            _ => false,
+        };
 
-        Ok(Format {
-            riff_tag,
-            total_size,
-            wave_tag,
-            fmt_chunk_tag,
-            fmt_chunk_size,
-            fmt_code,
-            num_channels,
-            sampling_rate,
-            byte_rate,
-            block_alignment,
-            bits_per_sample,
-            data_tag,
-            data_size,
-            data,
-        })
+        if !is_pcm {
+            return Err(anyhow::anyhow!(
+                "WAVE PCM format requires integer PCM (format code 1 or the extensible PCM sub-format), got format code {fmt_code} instead."
+            ));
+        }
+
+        Ok(())
     }
 
-    /// `check` checks if the WAVE PCM structure is properly-encoded.
+    /// `lufs_integrated` measures the integrated loudness of the 16-bit audio data in LUFS.
+    ///
+    /// The measurement follows ITU-R BS.1770 and EBU R128: every channel is K-weighted, the
+    /// mean square is taken over 400 ms blocks overlapping by 75 %, and blocks below the
+    /// absolute gate of -70 LUFS and the relative gate of -10 LU are discarded. All channels are
+    /// weighted equally, except for the 5.1 layout where the LFE channel is ignored and the
+    /// surround channels are weighted by 1.41. Audio shorter than one block, or entirely
+    /// gated, measures negative infinity.
     ///
     /// # Errors
     ///
-    /// Returns [`Err`](https://docs.rs/core/*/core/result/enum.Result.html) if the slice is not
-    /// UTF-8 with a description as to why the provided bytes are not UTF-8. The vector you moved
-    /// in is also included.
+    /// If the audio data is not 16 bits per sample.
     ///
     /// # Example
     ///
@@ -223,223 +8458,386 @@ impl Format {
     /// use wavepcm::Format;
     ///
     /// fn main() -> Result<(), anyhow::Error> {
-    ///     let decoding = Format::decode("sample.wav")?;
-    ///     decoding.check()?;
+    ///     // Stereo 1 kHz sine at -23 dBFS, the EBU R128 calibration signal.
+    ///     let amplitude = 32_768.0 * 10_f64.powf(-23.0 / 20.0);
+    ///     let data = (0..48_000 * 5)
+    ///         .map(|n| amplitude * (f64::from(n) * 1_000.0 * std::f64::consts::TAU / 48_000.0).sin())
+    ///         .flat_map(|x| [x.round() as i16; 2])
+    ///         .flat_map(|x| x.to_le_bytes())
+    ///         .collect();
+    ///     let encoding = Format::encode(data, 2, 48_000, 16)?;
+    ///
+    ///     assert!((encoding.lufs_integrated()? + 23.0).abs() < 0.1);
     ///     Ok(())
     /// }
     /// ```
-    pub fn check(&self) -> Result<(), anyhow::Error> {
-        let riff_tag_val = std::string::String::from_utf8(self.riff_tag.to_vec())?;
-        if riff_tag_val != "RIFF" {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires string \"RIFF\" as bytes 1 - 4, got {} instead.",
-                riff_tag_val
-            ));
-        }
-
-        let total_size_len = self.total_size.len();
-        if total_size_len != 4 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires 4 bytes as bytes 5 - 8, got {} instead.",
-                total_size_len
-            ));
-        }
-
-        let wave_tag_val = std::string::String::from_utf8(self.wave_tag.to_vec())?;
-        if wave_tag_val != "WAVE" {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires string \"WAVE\" as bytes 9 - 12, got {} instead.",
-                wave_tag_val
-            ));
-        }
-
-        let fmt_chunk_tag_val = std::string::String::from_utf8(self.fmt_chunk_tag.to_vec())?;
-        if fmt_chunk_tag_val != "fmt " {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires string \"fmt \" as bytes 13 - 16, got {} instead.",
-                fmt_chunk_tag_val
-            ));
-        }
-
-        let fmt_chunk_size_val = u32::from_le_bytes(self.fmt_chunk_size);
-        if fmt_chunk_size_val != 16 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires number 16 as bytes 17 - 20, got {} instead.",
-                fmt_chunk_size_val
-            ));
-        }
-
-        let fmt_code_val = u16::from_le_bytes(self.fmt_code);
-        if fmt_code_val != 1 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires number 1 as bytes 21 - 22, got {} instead.",
-                fmt_code_val
-            ));
-        }
-
-        let num_channels_len = self.num_channels.len();
-        if num_channels_len != 2 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires 2 bytes as bytes 23 - 24, got {} instead.",
-                num_channels_len
-            ));
-        }
+    #[cfg(feature = "std")]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn lufs_integrated(&self) -> Result<f64, anyhow::Error> {
+        let samples: Vec<f64> = self
+            .samples_i16()?
+            .iter()
+            .map(|&sample| f64::from(sample) / 32_768.0)
+            .collect();
+        let num_channels = usize::from(u16::from_le_bytes(self.num_channels).max(1));
+        let sampling_rate = u32::from_le_bytes(self.sampling_rate);
 
-        let sampling_rate_len = self.sampling_rate.len();
-        if sampling_rate_len != 4 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires 4 bytes as bytes 25 - 28, got {} instead.",
-                sampling_rate_len
-            ));
-        }
+        let [head, highpass] = BiquadCoeffs::k_weighting(sampling_rate);
+        let weighted = highpass.apply_f64(&head.apply_f64(&samples, num_channels), num_channels);
 
-        let byte_rate_len = self.byte_rate.len();
-        if byte_rate_len != 4 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires 4 bytes as bytes 29 - 32, got {} instead.",
-                byte_rate_len
-            ));
-        }
+        let channel_weight = |channel: usize| match (num_channels, channel) {
+            (6, 3) => 0.0,
+            (6, 4 | 5) => 1.41,
+            _ => 1.0,
+        };
 
-        let block_alignment_len = self.block_alignment.len();
-        if block_alignment_len != 2 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires 2 bytes as bytes 33 - 34, got {} instead.",
-                block_alignment_len
-            ));
+        // Prefix sums of the weighted squares, so every block is summed in constant time.
+        let mut energy = vec![0.0_f64];
+        for frame in weighted.chunks_exact(num_channels) {
+            let power: f64 = frame
+                .iter()
+                .enumerate()
+                .map(|(channel, sample)| channel_weight(channel) * sample * sample)
+                .sum();
+            energy.push(energy[energy.len() - 1] + power);
         }
 
-        let bits_per_sample_len = self.bits_per_sample.len();
-        if bits_per_sample_len != 2 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires 2 bytes as bytes 35 - 36, got {} instead.",
-                bits_per_sample_len
-            ));
+        let step = sampling_rate as usize / 10;
+        let block = 4 * step;
+        let frame_count = energy.len() - 1;
+        if block == 0 || step == 0 || frame_count < block {
+            return Ok(f64::NEG_INFINITY);
         }
 
-        let data_tag_val = std::string::String::from_utf8(self.data_tag.to_vec())?;
-        if data_tag_val != "data" {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires string \"data\" as bytes 37 - 40, got {} instead.",
-                data_tag_val
-            ));
+        let loudness = |power: f64| -0.691 + 10.0 * power.log10();
+        let blocks: Vec<f64> = (0..=(frame_count - block) / step)
+            .map(|idx| (energy[idx * step + block] - energy[idx * step]) / block as f64)
+            .filter(|&power| loudness(power) > -70.0)
+            .collect();
+        if blocks.is_empty() {
+            return Ok(f64::NEG_INFINITY);
         }
 
-        let data_size_len = self.data_size.len();
-        if data_size_len != 4 {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires 4 bytes as bytes 41 - 44, got {} instead.",
-                data_size_len
-            ));
+        let relative_gate = loudness(blocks.iter().sum::<f64>() / blocks.len() as f64) - 10.0;
+        let gated: Vec<f64> = blocks
+            .into_iter()
+            .filter(|&power| loudness(power) > relative_gate)
+            .collect();
+        if gated.is_empty() {
+            return Ok(f64::NEG_INFINITY);
         }
 
-        if self.data.is_empty() {
-            return Err(anyhow::anyhow!(
-                "WAVE PCM format requires at least one bytes as bytes 45 - EOF, got 0 instead."
-            ));
-        }
+        Ok(loudness(gated.iter().sum::<f64>() / gated.len() as f64))
+    }
 
-        Ok(())
+    /// `data_hash` fingerprints the audio data with 64-bit FNV-1a.
+    ///
+    /// Only the audio data is hashed, so two files with the same samples but different metadata
+    /// share a fingerprint. The value is stable across runs and platforms.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     let relabeled = encoding.relabel_sampling_rate(8_000)?;
+    ///     let other = Format::encode(vec![2u8; 16], 1, 16_000, 16)?;
+    ///
+    ///     assert_eq!(encoding.data_hash(), relabeled.data_hash());
+    ///     assert_ne!(encoding.data_hash(), other.data_hash());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn data_hash(&self) -> u64 {
+        self.data.iter().fold(0xCBF2_9CE4_8422_2325, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01B3)
+        })
     }
 
-    /// `info` prints the information about the WAVE PCM file.
+    /// `crc32` computes the CRC-32 checksum of the serialized file.
+    ///
+    /// The checksum covers the output of [`Format::to_bytes`] and matches the CRC-32 (IEEE
+    /// 802.3) used by zip, gzip and tools such as `crc32` or `rhash`, so it can be compared
+    /// against external manifests.
     ///
     /// # Errors
     ///
-    /// Returns [`Err`](https://docs.rs/core/*/core/result/enum.Result.html) if the slice is not
-    /// UTF-8 with a description as to why the provided bytes are not UTF-8. The vector you moved
-    /// in is also included.
+    /// If the file cannot be serialized, see [`Format::to_bytes`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::Format;
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1, 2, 3, 4], 1, 16_000, 16)?;
+    ///     assert_eq!(encoding.crc32()?, 0x0393_7E73);
+    ///
+    ///     let other = Format::encode(vec![1, 2, 3, 5], 1, 16_000, 16)?;
+    ///     assert_ne!(other.crc32()?, encoding.crc32()?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn crc32(&self) -> Result<u32, anyhow::Error> {
+        Ok(crc32(&self.to_bytes()?))
+    }
+
+    /// `white_noise` generates mono 16-bit white noise.
+    ///
+    /// Samples are uniformly distributed in [-`amplitude`, `amplitude`) of full scale and come
+    /// from a seeded generator, so the same arguments always produce the same data.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Length of the noise.
+    /// * `sampling_rate` - Sampling rate of the noise.
+    /// * `amplitude` - Peak level relative to full scale, between 0 and 1.
+    /// * `seed` - Seed of the pseudo-random generator.
     ///
     /// # Errors
     ///
-    /// If the value cannot fit when performing type conversion.
+    /// If the amplitude is not between 0 and 1 or the value cannot fit when performing type
+    /// conversion.
     ///
     /// # Example
     ///
     /// ```
+    /// use std::time::Duration;
     /// use wavepcm::Format;
     ///
     /// fn main() -> Result<(), anyhow::Error> {
-    ///     let decoding = Format::decode("sample.wav")?;
-    ///     decoding.check()?;
-    ///     decoding.info()?;
+    ///     let noise = Format::white_noise(Duration::from_millis(100), 16_000, 0.5, 7)?;
+    ///     assert_eq!(noise.frame_count(), 1_600);
+    ///     assert!(noise.analyze()?.peak <= 16_384);
+    ///
+    ///     let same = Format::white_noise(Duration::from_millis(100), 16_000, 0.5, 7)?;
+    ///     let other = Format::white_noise(Duration::from_millis(100), 16_000, 0.5, 8)?;
+    ///     assert_eq!(noise.data, same.data);
+    ///     assert_ne!(noise.data, other.data);
     ///     Ok(())
     /// }
     /// ```
-    pub fn info(&self) -> Result<(), anyhow::Error> {
-        let riff_tag = std::string::String::from_utf8(self.riff_tag.to_vec())?;
-        let total_size = u32::from_le_bytes(self.total_size);
-        let wave_tag = std::string::String::from_utf8(self.wave_tag.to_vec())?;
-        let fmt_chunk_tag = std::string::String::from_utf8(self.fmt_chunk_tag.to_vec())?;
-        let fmt_chunk_size = u32::from_le_bytes(self.fmt_chunk_size);
-        let fmt_code = u16::from_le_bytes(self.fmt_code);
-        let num_channels = u16::from_le_bytes(self.num_channels);
-        let sampling_rate = u32::from_le_bytes(self.sampling_rate);
-        let byte_rate = u32::from_le_bytes(self.byte_rate);
-        let block_alignment = u16::from_le_bytes(self.block_alignment);
-        let bits_per_sample = u16::from_le_bytes(self.bits_per_sample);
-        let data_tag = std::string::String::from_utf8(self.data_tag.to_vec())?;
-        let data_size = u32::from_le_bytes(self.data_size);
-
-        println!("RIFF TAG:           {:?}", riff_tag);
-        println!("TOTAL SIZE:         {:?}", total_size);
-        println!("WAVE TAG:           {:?}", wave_tag);
-        println!("FMT CHUNK TAG:      {:?}", fmt_chunk_tag);
-        println!("FMT CHUNK SIZE:     {:?}", fmt_chunk_size);
-        println!("FMT CODE:           {:?}", fmt_code);
-        println!("CHANNELS:           {:?}", num_channels);
-        println!("SAMPLING RATE:      {:?}", sampling_rate);
-        println!("BYTERATE:           {:?}", byte_rate);
-        println!("BLOCK ALIGNMENT:    {:?}", block_alignment);
-        println!("BITS PER SAMPLE:    {:?}", bits_per_sample);
-        println!("DATA TAG:           {:?}", data_tag);
-        println!("DATA SIZE:          {:?}\n", data_size);
-
-        Ok(())
+    #[cfg(feature = "std")]
+    pub fn white_noise(
+        duration: core::time::Duration,
+        sampling_rate: u32,
+        amplitude: f32,
+        seed: u64,
+    ) -> Result<Format, anyhow::Error> {
+        Format::noise(duration, sampling_rate, amplitude, seed, false)
     }
 
-    /// `write` writes a WAVE PCM file.
+    /// `pink_noise` generates mono 16-bit pink noise.
+    ///
+    /// White noise from a seeded generator is shaped to fall off by 3 dB per octave, so the same
+    /// arguments always produce the same data.
     ///
     /// # Arguments
     ///
-    /// * `path` - A path to the WAV PCM file.
+    /// * `duration` - Length of the noise.
+    /// * `sampling_rate` - Sampling rate of the noise.
+    /// * `amplitude` - Peak level relative to full scale, between 0 and 1.
+    /// * `seed` - Seed of the pseudo-random generator.
     ///
     /// # Errors
     ///
-    /// This function will return the first error of
-    /// non-[`ErrorKind::Interrupted`](https://docs.rs/std/*/std/io/error/enum.ErrorKind.html) kind
-    /// that [`write`](https://docs.rs/std/*/std/io/trait.Write.html#tymethod.write) returns.
+    /// If the amplitude is not between 0 and 1 or the value cannot fit when performing type
+    /// conversion.
     ///
     /// # Example
     ///
     /// ```
+    /// use std::time::Duration;
     /// use wavepcm::Format;
     ///
     /// fn main() -> Result<(), anyhow::Error> {
-    ///     let decoding = Format::decode("sample.wav")?;
-    ///     decoding.write("sample_copy.wav");
+    ///     let noise = Format::pink_noise(Duration::from_millis(100), 16_000, 1.0, 7)?;
+    ///     assert_eq!(noise.frame_count(), 1_600);
+    ///
+    ///     let same = Format::pink_noise(Duration::from_millis(100), 16_000, 1.0, 7)?;
+    ///     let other = Format::pink_noise(Duration::from_millis(100), 16_000, 1.0, 8)?;
+    ///     assert_eq!(noise.data, same.data);
+    ///     assert_ne!(noise.data, other.data);
     ///     Ok(())
     /// }
     /// ```
-    pub fn write(&self, path: &str) -> Result<(), anyhow::Error> {
-        let file = File::create(path)?;
-        let mut bufw = BufWriter::new(file);
+    #[cfg(feature = "std")]
+    pub fn pink_noise(
+        duration: core::time::Duration,
+        sampling_rate: u32,
+        amplitude: f32,
+        seed: u64,
+    ) -> Result<Format, anyhow::Error> {
+        Format::noise(duration, sampling_rate, amplitude, seed, true)
+    }
+
+    // Generate mono 16-bit white or pink noise.
+    //
+    // # Arguments
+    //
+    // * `duration` - Length of the noise.
+    // * `sampling_rate` - Sampling rate of the noise.
+    // * `amplitude` - Peak level relative to full scale, between 0 and 1.
+    // * `seed` - Seed of the pseudo-random generator.
+    // * `pink` - Whether the white noise is shaped to pink noise.
+    //
+    // # Errors
+    //
+    // If the amplitude is not between 0 and 1 or the value cannot fit when performing type
+    // conversion.
+    #[cfg(feature = "std")]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn noise(
+        duration: core::time::Duration,
+        sampling_rate: u32,
+        amplitude: f32,
+        seed: u64,
+        pink: bool,
+    ) -> Result<Format, anyhow::Error> {
+        if !(0.0..=1.0).contains(&amplitude) {
+            return Err(anyhow::anyhow!(
+                "Noise amplitude must be between 0 and 1, got {amplitude} instead."
+            ));
+        }
 
-        bufw.write_all(&self.riff_tag)?;
-        bufw.write_all(&self.total_size)?;
-        bufw.write_all(&self.wave_tag)?;
-        bufw.write_all(&self.fmt_chunk_tag)?;
-        bufw.write_all(&self.fmt_chunk_size)?;
-        bufw.write_all(&self.fmt_code)?;
-        bufw.write_all(&self.num_channels)?;
-        bufw.write_all(&self.sampling_rate)?;
-        bufw.write_all(&self.byte_rate)?;
-        bufw.write_all(&self.block_alignment)?;
-        bufw.write_all(&self.bits_per_sample)?;
-        bufw.write_all(&self.data_tag)?;
-        bufw.write_all(&self.data_size)?;
-        bufw.write_all(&self.data)?;
+        let frames = usize::try_from(duration_to_frames(duration, sampling_rate))?;
+        let scale = f64::from(amplitude) * 32_768.0;
+        let mut rng = XorShift::new(seed);
+        // Paul Kellet's pink noise filter state, its output peaks near 1 after scaling by 0.11.
+        let mut state = [0.0_f64; 7];
+        let samples: Vec<i16> = (0..frames)
+            .map(|_| {
+                let white = rng.next_f64() * 2.0 - 1.0;
+                if !pink {
+                    return saturate_i16(white * scale);
+                }
+                state[0] = 0.998_86 * state[0] + white * 0.055_517_9;
+                state[1] = 0.993_32 * state[1] + white * 0.075_075_9;
+                state[2] = 0.969_00 * state[2] + white * 0.153_852_0;
+                state[3] = 0.866_50 * state[3] + white * 0.310_485_6;
+                state[4] = 0.550_00 * state[4] + white * 0.532_952_2;
+                state[5] = -0.761_6 * state[5] - white * 0.016_898_0;
+                let shaped = state.iter().sum::<f64>() + white * 0.536_2;
+                state[6] = white * 0.115_926;
+                saturate_i16(shaped * 0.11 * scale)
+            })
+            .collect();
 
-        Ok(())
+        Format::encode(i16_to_bytes(&samples), 1, sampling_rate, 16)
+    }
+
+    /// `diff` lists the differences between two files.
+    ///
+    /// Every header field and metadata chunk that differs is reported with both values, header
+    /// fields first in file order. Differing audio data is reported last, with the offset of the
+    /// first differing byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - File to compare with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wavepcm::{FieldDiff, Format};
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let encoding = Format::encode(vec![1u8; 16], 1, 16_000, 16)?;
+    ///     assert!(encoding.diff(&encoding).is_empty());
+    ///
+    ///     let mut other = encoding.clone();
+    ///     other.sampling_rate = 8_000_u32.to_le_bytes();
+    ///     assert_eq!(
+    ///         encoding.diff(&other),
+    ///         vec![FieldDiff::Field {
+    ///             name: "sampling_rate",
+    ///             left: "16000".to_string(),
+    ///             right: "8000".to_string(),
+    ///         }]
+    ///     );
+    ///
+    ///     other.data[5] = 0;
+    ///     assert_eq!(encoding.diff(&other)[1], FieldDiff::Data { first_byte: 5 });
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Format) -> Vec<FieldDiff> {
+        let mut diffs: Vec<FieldDiff> = self
+            .diff_fields()
+            .into_iter()
+            .zip(other.diff_fields())
+            .filter(|((_, left), (_, right))| left != right)
+            .map(|((name, left), (_, right))| FieldDiff::Field { name, left, right })
+            .collect();
+
+        if self.data != other.data {
+            let first_byte = self
+                .data
+                .iter()
+                .zip(&other.data)
+                .position(|(left, right)| left != right)
+                .unwrap_or_else(|| self.data.len().min(other.data.len()));
+            diffs.push(FieldDiff::Data { first_byte });
+        }
+
+        diffs
+    }
+
+    // Return the name and printed value of every field compared by `diff`.
+    fn diff_fields(&self) -> Vec<(&'static str, String)> {
+        let tag = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+        vec![
+            ("riff_tag", tag(&self.riff_tag)),
+            (
+                "total_size",
+                format!("{}", u32::from_le_bytes(self.total_size)),
+            ),
+            ("wave_tag", tag(&self.wave_tag)),
+            ("fmt_chunk_tag", tag(&self.fmt_chunk_tag)),
+            (
+                "fmt_chunk_size",
+                format!("{}", u32::from_le_bytes(self.fmt_chunk_size)),
+            ),
+            ("fmt_code", format!("{}", u16::from_le_bytes(self.fmt_code))),
+            (
+                "num_channels",
+                format!("{}", u16::from_le_bytes(self.num_channels)),
+            ),
+            (
+                "sampling_rate",
+                format!("{}", u32::from_le_bytes(self.sampling_rate)),
+            ),
+            (
+                "byte_rate",
+                format!("{}", u32::from_le_bytes(self.byte_rate)),
+            ),
+            (
+                "block_alignment",
+                format!("{}", u16::from_le_bytes(self.block_alignment)),
+            ),
+            (
+                "bits_per_sample",
+                format!("{}", u16::from_le_bytes(self.bits_per_sample)),
+            ),
+            ("fmt_extension", format!("{:?}", self.fmt_extension)),
+            ("data_tag", tag(&self.data_tag)),
+            (
+                "data_size",
+                format!("{}", u32::from_le_bytes(self.data_size)),
+            ),
+            ("sample_loops", format!("{:?}", self.sample_loops)),
+            ("sampler", format!("{:?}", self.sampler)),
+            ("broadcast_info", format!("{:?}", self.broadcast_info)),
+            ("instrument", format!("{:?}", self.instrument)),
+            ("cue_points", format!("{:?}", self.cue_points)),
+            ("leading_chunks", format!("{:?}", self.leading_chunks)),
+            ("trailing_chunks", format!("{:?}", self.trailing_chunks)),
+            ("chunk_order", format!("{:?}", self.chunk_order)),
+            ("file_size", format!("{:?}", self.file_size)),
+        ]
     }
 }